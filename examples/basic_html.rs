@@ -52,14 +52,11 @@ fn main() {
 
     ul_app.overlay_resize(853u32, 480u32);
 
-    let mut finished = |_| println!("loaded!");
-    let mut dom_ready = |_| println!("dom ready!");
+    ul.set_finish_loading_callback(|_| println!("loaded!"));
+    ul.set_dom_ready_callback(|_| println!("dom ready!"));
 
-    ul.set_finish_loading_callback(&mut finished);
-    ul.set_dom_ready_callback(&mut dom_ready);
-
-    ul_app.window_set_resize_callback(&mut |width: u32, height: u32| {
-        ul_app.overlay_resize(width, height);
+    ul_app.window_set_resize_callback(|overlay: &ul::AppOverlay, width: u32, height: u32| {
+        overlay.resize(width, height);
     });
 
     ul_app.run();
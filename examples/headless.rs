@@ -0,0 +1,36 @@
+use ::ul as ul;
+
+fn main() {
+    let config = ul::Config::new();
+
+    // the directory containing the SDK's resources/ folder
+    let renderer = ul::Renderer::headless(&config, "./");
+
+    let mut view = renderer.create_view(853u32, 480u32, None);
+
+    view.load_html(r#"
+        <html>
+            <head>
+                <style>
+                    body {
+                        background-color: black;
+                        color: white;
+                        font-size: 100px;
+                    }
+                </style>
+            </head>
+            <body>Hello</body>
+        </html>"#)
+        .expect("failed to load HTML");
+
+    renderer
+        .update_until_loaded(&mut view, &ul::WaitOptions::default())
+        .expect("page did not load in time");
+
+    let pixels = view.get_raw_pixels().expect("view has no pixels");
+
+    println!("rendered {} bytes", pixels.len());
+
+    view.write_png_to_file("headless.png")
+        .expect("failed to write headless.png");
+}
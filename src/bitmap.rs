@@ -0,0 +1,363 @@
+use crate::Error;
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_void;
+#[cfg(feature = "image")]
+use std::path::Path;
+
+/// Pixel format of a `Bitmap`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum BitmapFormat {
+    /// Alpha channel only, 8 bits per pixel.
+    A8Unorm,
+    /// Blue, green, red and alpha channels, 32 bits per pixel, sRGB with
+    /// premultiplied alpha.
+    Bgra8UnormSrgb,
+}
+
+impl BitmapFormat {
+    fn from_raw(format: ul_sys::ULBitmapFormat) -> BitmapFormat {
+        match format {
+            ul_sys::ULBitmapFormat_kBitmapFormat_A8_UNORM => BitmapFormat::A8Unorm,
+            _ => BitmapFormat::Bgra8UnormSrgb,
+        }
+    }
+
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            BitmapFormat::A8Unorm => 1,
+            BitmapFormat::Bgra8UnormSrgb => 4,
+        }
+    }
+
+    fn to_raw(self) -> ul_sys::ULBitmapFormat {
+        match self {
+            BitmapFormat::A8Unorm => ul_sys::ULBitmapFormat_kBitmapFormat_A8_UNORM,
+            BitmapFormat::Bgra8UnormSrgb => ul_sys::ULBitmapFormat_kBitmapFormat_BGRA8_UNORM_SRGB,
+        }
+    }
+}
+
+/// A pixel buffer.
+///
+/// Bitmaps created through `Bitmap::new`, `Bitmap::from_pixels` or
+/// `Bitmap::copy` are owned and destroyed when dropped. Bitmaps returned
+/// by a `View` are owned by its surface and borrow the view.
+pub struct Bitmap<'a> {
+    raw: ul_sys::ULBitmap,
+    owned: bool,
+
+    phantom: PhantomData<&'a ()>,
+}
+
+impl Bitmap<'static> {
+    /// Creates a zeroed bitmap with the given dimensions and format.
+    pub fn new(width: u32, height: u32, format: BitmapFormat) -> Bitmap<'static> {
+        unsafe { Bitmap::from_raw(ul_sys::ulCreateBitmap(width, height, format.to_raw()), true) }
+    }
+
+    /// Creates a bitmap from a copy of `pixels`, which are laid out in
+    /// rows of `row_bytes` bytes.
+    ///
+    /// Fails with `Error::InvalidBitmapLayout` if a row is too short for
+    /// `width` pixels or `pixels` is too short for `height` rows.
+    pub fn from_pixels(
+        width: u32,
+        height: u32,
+        format: BitmapFormat,
+        row_bytes: u32,
+        pixels: &[u8],
+    ) -> Result<Bitmap<'static>, Error> {
+        let min_row_bytes = width as usize * format.bytes_per_pixel();
+        if (row_bytes as usize) < min_row_bytes {
+            return Err(Error::InvalidBitmapLayout(format!(
+                "{} row bytes can't hold {} pixels",
+                row_bytes, width
+            )));
+        }
+
+        let min_len = row_bytes as usize * height as usize;
+        if pixels.len() < min_len {
+            return Err(Error::InvalidBitmapLayout(format!(
+                "{} rows of {} bytes need {} bytes, got {}",
+                height,
+                row_bytes,
+                min_len,
+                pixels.len()
+            )));
+        }
+
+        unsafe {
+            Ok(Bitmap::from_raw(
+                ul_sys::ulCreateBitmapFromPixels(
+                    width,
+                    height,
+                    format.to_raw(),
+                    row_bytes,
+                    pixels.as_ptr() as *const c_void,
+                    pixels.len() as _,
+                    true,
+                ),
+                true,
+            ))
+        }
+    }
+}
+
+impl<'a> Bitmap<'a> {
+    pub(crate) unsafe fn from_raw(raw: ul_sys::ULBitmap, owned: bool) -> Bitmap<'a> {
+        Bitmap {
+            raw,
+            owned,
+
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the underlying `ULBitmap`.
+    pub fn raw(&self) -> ul_sys::ULBitmap {
+        self.raw
+    }
+
+    /// Creates an owned copy of this bitmap.
+    pub fn copy(&self) -> Bitmap<'static> {
+        unsafe { Bitmap::from_raw(ul_sys::ulCreateBitmapFromCopy(self.raw), true) }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { ul_sys::ulBitmapGetWidth(self.raw) }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { ul_sys::ulBitmapGetHeight(self.raw) }
+    }
+
+    pub fn row_bytes(&self) -> u32 {
+        unsafe { ul_sys::ulBitmapGetRowBytes(self.raw) }
+    }
+
+    pub fn bytes_per_pixel(&self) -> u32 {
+        unsafe { ul_sys::ulBitmapGetBpp(self.raw) }
+    }
+
+    /// Size in bytes of the underlying pixel buffer.
+    pub fn size(&self) -> usize {
+        unsafe { ul_sys::ulBitmapGetSize(self.raw) as usize }
+    }
+
+    pub fn format(&self) -> BitmapFormat {
+        BitmapFormat::from_raw(unsafe { ul_sys::ulBitmapGetFormat(self.raw) })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { ul_sys::ulBitmapIsEmpty(self.raw) }
+    }
+
+    /// Locks the pixels for reading, they are unlocked when the returned
+    /// guard is dropped.
+    pub fn lock(&self) -> Result<Pixels, Error> {
+        let (data, len) = unsafe { self.lock_raw()? };
+
+        Ok(Pixels {
+            raw: self.raw,
+            data,
+            len,
+
+            phantom: PhantomData,
+        })
+    }
+
+    /// Locks the pixels for reading and writing, they are unlocked when
+    /// the returned guard is dropped.
+    pub fn lock_mut(&mut self) -> Result<PixelsMut, Error> {
+        let (data, len) = unsafe { self.lock_raw()? };
+
+        Ok(PixelsMut {
+            raw: self.raw,
+            data,
+            len,
+
+            phantom: PhantomData,
+        })
+    }
+
+    /// Copies the pixels into a tightly packed (`width * 4` bytes per row)
+    /// RGBA buffer with straight (non-premultiplied) alpha.
+    ///
+    /// `A8Unorm` bitmaps are expanded to black with the coverage as alpha.
+    pub fn to_rgba(&self) -> Result<Vec<u8>, Error> {
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+        let row_bytes = self.row_bytes() as usize;
+        let format = self.format();
+
+        // empty bitmaps have no rows to convert (and no pixels to lock)
+        if width == 0 || height == 0 || row_bytes == 0 {
+            return Ok(Vec::new());
+        }
+
+        let pixels = self.lock()?;
+
+        let mut rgba = Vec::with_capacity(width * height * 4);
+
+        for row in pixels.chunks(row_bytes).take(height) {
+            match format {
+                BitmapFormat::A8Unorm => {
+                    for &a in &row[..width] {
+                        rgba.extend_from_slice(&[0, 0, 0, a]);
+                    }
+                }
+                BitmapFormat::Bgra8UnormSrgb => {
+                    for bgra in row[..width * 4].chunks(4) {
+                        let a = bgra[3];
+
+                        rgba.extend_from_slice(&[
+                            unpremultiply(bgra[2], a),
+                            unpremultiply(bgra[1], a),
+                            unpremultiply(bgra[0], a),
+                            a,
+                        ]);
+                    }
+                }
+            }
+        }
+
+        Ok(rgba)
+    }
+
+    /// Writes the bitmap to a PNG file.
+    pub fn write_png<S: AsRef<str>>(&self, file_name: S) -> Result<(), Error> {
+        let file_name = file_name.as_ref();
+
+        let fn_c_str = std::ffi::CString::new(file_name)?;
+
+        if unsafe { !ul_sys::ulBitmapWritePNG(self.raw, fn_c_str.as_ptr()) } {
+            return Err(Error::PngWriteFailed(file_name.to_string()));
+        }
+
+        Ok(())
+    }
+
+    unsafe fn lock_raw(&self) -> Result<(*mut u8, usize), Error> {
+        let data = ul_sys::ulBitmapLockPixels(self.raw);
+
+        if data.is_null() {
+            return Err(Error::BitmapLockFailed);
+        }
+
+        Ok((data as *mut u8, self.size()))
+    }
+}
+
+#[cfg(feature = "image")]
+impl<'a> Bitmap<'a> {
+    /// Converts the bitmap into an `image::RgbaImage` with straight alpha.
+    pub fn to_image(&self) -> Result<image::RgbaImage, Error> {
+        let rgba = self.to_rgba()?;
+
+        // to_rgba always returns width * height * 4 bytes
+        Ok(image::RgbaImage::from_raw(self.width(), self.height(), rgba)
+            .expect("RGBA buffer matches the bitmap dimensions"))
+    }
+
+    /// Encodes the bitmap with the `image` crate, guessing the format
+    /// (PNG, JPEG, WebP or BMP) from the extension of `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let format = image::ImageFormat::from_path(path.as_ref())?;
+
+        self.save_with_format(path, format)
+    }
+
+    /// Encodes the bitmap in the given format with the `image` crate.
+    ///
+    /// Formats without an alpha channel (eg. JPEG) drop it.
+    pub fn save_with_format<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: image::ImageFormat,
+    ) -> Result<(), Error> {
+        let image = image::DynamicImage::ImageRgba8(self.to_image()?);
+
+        match format {
+            image::ImageFormat::Jpeg => image.to_rgb8().save_with_format(path, format)?,
+            _ => image.save_with_format(path, format)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Drop for Bitmap<'a> {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe {
+                ul_sys::ulDestroyBitmap(self.raw);
+            }
+        }
+    }
+}
+
+/// Read access to the locked pixels of a `Bitmap`.
+pub struct Pixels<'b> {
+    raw: ul_sys::ULBitmap,
+    data: *mut u8,
+    len: usize,
+
+    phantom: PhantomData<&'b [u8]>,
+}
+
+impl<'b> Deref for Pixels<'b> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<'b> Drop for Pixels<'b> {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulBitmapUnlockPixels(self.raw);
+        }
+    }
+}
+
+/// Read and write access to the locked pixels of a `Bitmap`.
+pub struct PixelsMut<'b> {
+    raw: ul_sys::ULBitmap,
+    data: *mut u8,
+    len: usize,
+
+    phantom: PhantomData<&'b mut [u8]>,
+}
+
+impl<'b> Deref for PixelsMut<'b> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<'b> DerefMut for PixelsMut<'b> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<'b> Drop for PixelsMut<'b> {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulBitmapUnlockPixels(self.raw);
+        }
+    }
+}
+
+fn unpremultiply(channel: u8, alpha: u8) -> u8 {
+    match alpha {
+        0 => 0,
+        255 => channel,
+        _ => ((channel as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8,
+    }
+}
@@ -0,0 +1,302 @@
+use crate::helpers_internal::js_string_literal;
+use crate::{Bitmap, BitmapFormat, Error, Renderer, View};
+
+/// How `Renderer::capture_full_page` gets the parts of the page outside of
+/// the viewport on screen.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CaptureMode {
+    /// Temporarily resize the view to the height of the document and
+    /// render it in one go. Fast, but elements sized relative to the
+    /// viewport (eg. `height: 100vh`) grow with it.
+    Resize,
+    /// Scroll through the document in viewport sized steps and stitch the
+    /// rendered tiles together. Fails with `Error::NotScrollable` if the
+    /// document doesn't scroll.
+    Stitch,
+}
+
+/// Options for `Renderer::capture_full_page`.
+#[derive(Clone, Debug)]
+pub struct FullPageCapture {
+    pub mode: CaptureMode,
+    /// Maximum height of the captured bitmap in pixels, longer documents
+    /// are cut off.
+    pub max_height: Option<u32>,
+    /// Hide `position: fixed` and `position: sticky` elements on every tile
+    /// but the first one, so headers don't repeat throughout the capture.
+    /// Only used when stitching.
+    pub hide_fixed_elements: bool,
+}
+
+impl Default for FullPageCapture {
+    fn default() -> Self {
+        FullPageCapture {
+            mode: CaptureMode::Stitch,
+            max_height: Some(16384),
+            hide_fixed_elements: true,
+        }
+    }
+}
+
+const HIDE_FIXED_ELEMENTS: &str = "\
+    window.__ulHiddenFixedElements = [];\
+    document.querySelectorAll('*').forEach(function(e) {\
+        var position = getComputedStyle(e).position;\
+        if (position === 'fixed' || position === 'sticky') {\
+            window.__ulHiddenFixedElements.push([e, e.style.visibility]);\
+            e.style.visibility = 'hidden';\
+        }\
+    });";
+
+const SHOW_FIXED_ELEMENTS: &str = "\
+    (window.__ulHiddenFixedElements || []).forEach(function(h) {\
+        h[0].style.visibility = h[1];\
+    });\
+    delete window.__ulHiddenFixedElements;";
+
+impl Renderer {
+    /// Captures the whole document of `view` rather than only its viewport.
+    ///
+    /// The view is scrolled or resized while capturing and restored
+    /// afterwards.
+    pub fn capture_full_page(
+        &self,
+        view: &mut View,
+        options: &FullPageCapture,
+    ) -> Result<Bitmap<'static>, Error> {
+        let scale = view.device_scale()?;
+        let (scroll_x, scroll_y) = view.scroll_position()?;
+
+        let document_height = (view
+            .evaluate_script(
+                "Math.max(document.body.scrollHeight, document.documentElement.scrollHeight)",
+            )?
+            .as_number()?
+            * scale)
+            .ceil() as u32;
+
+        let height = match options.max_height {
+            Some(max_height) => document_height.min(max_height),
+            None => document_height,
+        }
+        .max(1);
+
+        let result = match options.mode {
+            CaptureMode::Resize => self.capture_resized(view, height),
+            CaptureMode::Stitch => self.capture_stitched(view, height, scale, options),
+        };
+
+        // a failed capture is reported over a failed restore
+        let restored = view.scroll_to(scroll_x, scroll_y);
+        self.repaint(view);
+
+        let page = result?;
+        restored?;
+
+        Ok(page)
+    }
+
+    fn capture_resized(&self, view: &mut View, height: u32) -> Result<Bitmap<'static>, Error> {
+        let (width, viewport_height) = (view.width(), view.height());
+
+        view.resize(width, height);
+        self.repaint(view);
+
+        let result = view.bitmap().map(|bitmap| bitmap.copy());
+
+        view.resize(width, viewport_height);
+
+        result
+    }
+
+    fn capture_stitched(
+        &self,
+        view: &mut View,
+        height: u32,
+        scale: f64,
+        options: &FullPageCapture,
+    ) -> Result<Bitmap<'static>, Error> {
+        let (width, viewport_height) = (view.width(), view.height());
+
+        let mut page = Bitmap::new(width, height, BitmapFormat::Bgra8UnormSrgb);
+        let page_row_bytes = page.row_bytes() as usize;
+
+        let mut hid_fixed_elements = false;
+        let mut y = 0;
+
+        let result = (|| -> Result<(), Error> {
+            while y < height {
+                view.scroll_to(0.0, y as f64 / scale)?;
+                self.repaint(view);
+
+                // the last tile can't scroll past the end of the document, so
+                // it overlaps the previous one
+                let scrolled = (view.scroll_position()?.1 * scale).round() as u32;
+                let offset = y.saturating_sub(scrolled).min(viewport_height);
+                let rows = (viewport_height - offset).min(height - y);
+
+                // the rest of the page would stay blank
+                if rows == 0 {
+                    return Err(Error::NotScrollable);
+                }
+
+                {
+                    let tile = view.bitmap()?;
+                    let tile_row_bytes = tile.row_bytes() as usize;
+                    let row_len = tile_row_bytes.min(page_row_bytes);
+
+                    let src = tile.lock()?;
+                    let mut dst = page.lock_mut()?;
+
+                    for row in 0..rows as usize {
+                        let src_start = (offset as usize + row) * tile_row_bytes;
+                        let dst_start = (y as usize + row) * page_row_bytes;
+
+                        dst[dst_start..dst_start + row_len]
+                            .copy_from_slice(&src[src_start..src_start + row_len]);
+                    }
+                }
+
+                if options.hide_fixed_elements && !hid_fixed_elements {
+                    view.evaluate_script(HIDE_FIXED_ELEMENTS)?;
+                    hid_fixed_elements = true;
+                }
+
+                y += rows;
+            }
+
+            Ok(())
+        })();
+
+        let cleanup = if hid_fixed_elements {
+            view.evaluate_script(SHOW_FIXED_ELEMENTS).map(|_| ())
+        } else {
+            Ok(())
+        };
+
+        result?;
+        cleanup?;
+
+        Ok(page)
+    }
+
+    /// Captures the first element matching the CSS `selector`, scrolling
+    /// it into view first if it isn't visible completely.
+    ///
+    /// Parts of the element that don't fit into the viewport are cut off.
+    /// The scroll position of the view is restored afterwards.
+    pub fn capture_element<S: AsRef<str>>(
+        &self,
+        view: &mut View,
+        selector: S,
+    ) -> Result<Bitmap<'static>, Error> {
+        let (scroll_x, scroll_y) = view.scroll_position()?;
+
+        let result = self.capture_visible_element(view, selector.as_ref());
+
+        // a failed capture is reported over a failed restore
+        let restored = view.scroll_to(scroll_x, scroll_y);
+        self.repaint(view);
+
+        let element = result?;
+        restored?;
+
+        Ok(element)
+    }
+
+    fn capture_visible_element(
+        &self,
+        view: &mut View,
+        selector: &str,
+    ) -> Result<Bitmap<'static>, Error> {
+        let scale = view.device_scale()?;
+
+        let (mut left, mut top, width, height) = element_rect(view, selector)?;
+
+        let viewport_width = view.width() as f64 / scale;
+        let viewport_height = view.height() as f64 / scale;
+
+        let fully_visible = left >= 0.0
+            && top >= 0.0
+            && left + width <= viewport_width
+            && top + height <= viewport_height;
+
+        if !fully_visible {
+            view.scroll_to_element(selector)?;
+            self.repaint(view);
+
+            let rect = element_rect(view, selector)?;
+            left = rect.0;
+            top = rect.1;
+        } else {
+            self.repaint(view);
+        }
+
+        let x0 = ((left * scale).floor().max(0.0) as u32).min(view.width());
+        let y0 = ((top * scale).floor().max(0.0) as u32).min(view.height());
+        let x1 = (((left + width) * scale).ceil().max(0.0) as u32).min(view.width());
+        let y1 = (((top + height) * scale).ceil().max(0.0) as u32).min(view.height());
+
+        if x1 <= x0 || y1 <= y0 {
+            return Err(Error::ElementNotVisible(selector.to_string()));
+        }
+
+        let mut element = Bitmap::new(x1 - x0, y1 - y0, BitmapFormat::Bgra8UnormSrgb);
+
+        {
+            let element_row_bytes = element.row_bytes() as usize;
+
+            let tile = view.bitmap()?;
+            let tile_row_bytes = tile.row_bytes() as usize;
+            let bpp = tile.bytes_per_pixel() as usize;
+
+            let src = tile.lock()?;
+            let mut dst = element.lock_mut()?;
+
+            let row_len = (x1 - x0) as usize * bpp;
+
+            for row in 0..(y1 - y0) as usize {
+                let src_start = (y0 as usize + row) * tile_row_bytes + x0 as usize * bpp;
+                let dst_start = row * element_row_bytes;
+
+                dst[dst_start..dst_start + row_len]
+                    .copy_from_slice(&src[src_start..src_start + row_len]);
+            }
+        }
+
+        Ok(element)
+    }
+
+    // Lays out and paints the view again after it was scrolled, resized or
+    // its DOM was changed.
+    fn repaint(&self, view: &mut View) {
+        view.set_needs_paint(true);
+
+        self.update();
+        self.render();
+    }
+}
+
+// Returns the bounding client rect (left, top, width, height) of the first
+// element matching `selector`, in CSS pixels relative to the viewport.
+fn element_rect(view: &mut View, selector: &str) -> Result<(f64, f64, f64, f64), Error> {
+    let rect = view.evaluate_script(format!(
+        "(function(e) {{\
+            if (!e) return null;\
+            var r = e.getBoundingClientRect();\
+            return [r.left, r.top, r.width, r.height];\
+        }})(document.querySelector({}))",
+        js_string_literal(selector)
+    ))?;
+
+    if rect.is_null() {
+        return Err(Error::ElementNotFound(selector.to_string()));
+    }
+
+    let rect = rect.as_object()?;
+    let number = |i| -> Result<f64, Error> {
+        Ok(rect.get_property_at_index(i)?.as_number()?)
+    };
+
+    Ok((number(0)?, number(1)?, number(2)?, number(3)?))
+}
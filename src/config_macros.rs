@@ -24,13 +24,11 @@ macro_rules! set_config_str (
     ($config: expr, $self: expr, $name:ident, $ffiName:ident) => (
         if $self.$name.is_some() {
             unsafe {
-                let str = ul_sys::ulCreateString(
-                    std::ffi::CString::new(
-                        $self.$name.clone().unwrap()
-                    ).unwrap().as_ptr()
+                let str = $crate::helpers_internal::ul_string(
+                    $self.$name.as_ref().unwrap()
                 );
 
-                ul_sys::$ffiName($config, str);
+                ul_sys::$ffiName($config, str.raw);
             }
         }
     )
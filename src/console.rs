@@ -0,0 +1,153 @@
+use crate::helpers_internal::from_ul_string;
+
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum MessageSource {
+    XML = ul_sys::ULMessageSource_kMessageSource_XML as isize,
+    JS = ul_sys::ULMessageSource_kMessageSource_JS as isize,
+    Network = ul_sys::ULMessageSource_kMessageSource_Network as isize,
+    ConsoleAPI = ul_sys::ULMessageSource_kMessageSource_ConsoleAPI as isize,
+    Storage = ul_sys::ULMessageSource_kMessageSource_Storage as isize,
+    AppCache = ul_sys::ULMessageSource_kMessageSource_AppCache as isize,
+    Rendering = ul_sys::ULMessageSource_kMessageSource_Rendering as isize,
+    CSS = ul_sys::ULMessageSource_kMessageSource_CSS as isize,
+    Security = ul_sys::ULMessageSource_kMessageSource_Security as isize,
+    ContentBlocker = ul_sys::ULMessageSource_kMessageSource_ContentBlocker as isize,
+    Other = ul_sys::ULMessageSource_kMessageSource_Other as isize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum MessageLevel {
+    Log = ul_sys::ULMessageLevel_kMessageLevel_Log as isize,
+    Warning = ul_sys::ULMessageLevel_kMessageLevel_Warning as isize,
+    Error = ul_sys::ULMessageLevel_kMessageLevel_Error as isize,
+    Debug = ul_sys::ULMessageLevel_kMessageLevel_Debug as isize,
+    Info = ul_sys::ULMessageLevel_kMessageLevel_Info as isize,
+}
+
+/// A message added to the console of a view, eg. through `console.log` or
+/// because of a JavaScript or network error.
+#[derive(Clone, Debug)]
+pub struct ConsoleMessage {
+    pub source: MessageSource,
+    pub level: MessageLevel,
+    pub text: String,
+    pub line: u32,
+    pub column: u32,
+    /// URL of the script or resource the message originates from.
+    pub source_id: String,
+}
+
+impl MessageSource {
+    fn from_raw(source: ul_sys::ULMessageSource) -> MessageSource {
+        match source {
+            ul_sys::ULMessageSource_kMessageSource_XML => MessageSource::XML,
+            ul_sys::ULMessageSource_kMessageSource_JS => MessageSource::JS,
+            ul_sys::ULMessageSource_kMessageSource_Network => MessageSource::Network,
+            ul_sys::ULMessageSource_kMessageSource_ConsoleAPI => MessageSource::ConsoleAPI,
+            ul_sys::ULMessageSource_kMessageSource_Storage => MessageSource::Storage,
+            ul_sys::ULMessageSource_kMessageSource_AppCache => MessageSource::AppCache,
+            ul_sys::ULMessageSource_kMessageSource_Rendering => MessageSource::Rendering,
+            ul_sys::ULMessageSource_kMessageSource_CSS => MessageSource::CSS,
+            ul_sys::ULMessageSource_kMessageSource_Security => MessageSource::Security,
+            ul_sys::ULMessageSource_kMessageSource_ContentBlocker => MessageSource::ContentBlocker,
+            _ => MessageSource::Other,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageSource::XML => "xml",
+            MessageSource::JS => "js",
+            MessageSource::Network => "network",
+            MessageSource::ConsoleAPI => "consoleapi",
+            MessageSource::Storage => "storage",
+            MessageSource::AppCache => "appcache",
+            MessageSource::Rendering => "rendering",
+            MessageSource::CSS => "css",
+            MessageSource::Security => "security",
+            MessageSource::ContentBlocker => "contentblocker",
+            MessageSource::Other => "other",
+        }
+    }
+}
+
+impl MessageLevel {
+    fn from_raw(level: ul_sys::ULMessageLevel) -> MessageLevel {
+        match level {
+            ul_sys::ULMessageLevel_kMessageLevel_Warning => MessageLevel::Warning,
+            ul_sys::ULMessageLevel_kMessageLevel_Error => MessageLevel::Error,
+            ul_sys::ULMessageLevel_kMessageLevel_Debug => MessageLevel::Debug,
+            ul_sys::ULMessageLevel_kMessageLevel_Info => MessageLevel::Info,
+            _ => MessageLevel::Log,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageLevel::Log => "log",
+            MessageLevel::Warning => "warning",
+            MessageLevel::Error => "error",
+            MessageLevel::Debug => "debug",
+            MessageLevel::Info => "info",
+        }
+    }
+}
+
+impl ConsoleMessage {
+    pub(crate) unsafe fn from_raw(
+        source: ul_sys::ULMessageSource,
+        level: ul_sys::ULMessageLevel,
+        text: ul_sys::ULString,
+        line: u32,
+        column: u32,
+        source_id: ul_sys::ULString,
+    ) -> ConsoleMessage {
+        ConsoleMessage {
+            source: MessageSource::from_raw(source),
+            level: MessageLevel::from_raw(level),
+            text: from_ul_string(text).unwrap_or_default(),
+            line,
+            column,
+            source_id: from_ul_string(source_id).unwrap_or_default(),
+        }
+    }
+
+    /// Logs the message through the `log` crate, with target `ul::console`.
+    #[cfg(feature = "log")]
+    pub fn log(&self) {
+        let level = match self.level {
+            MessageLevel::Log | MessageLevel::Info => log::Level::Info,
+            MessageLevel::Warning => log::Level::Warn,
+            MessageLevel::Error => log::Level::Error,
+            MessageLevel::Debug => log::Level::Debug,
+        };
+
+        log::log!(
+            target: "ul::console",
+            level,
+            "[{}] {} ({}:{}:{})",
+            self.source.name(),
+            self.text,
+            self.source_id,
+            self.line,
+            self.column
+        );
+    }
+}
+
+impl fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{}] [{}] {} ({}:{}:{})",
+            self.level.name(),
+            self.source.name(),
+            self.text,
+            self.source_id,
+            self.line,
+            self.column
+        )
+    }
+}
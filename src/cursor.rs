@@ -1,3 +1,4 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum Cursor {
     Alias = ul_sys::ULCursor_kCursor_Alias as isize,
     Cell = ul_sys::ULCursor_kCursor_Cell as isize,
@@ -44,3 +45,55 @@ pub enum Cursor {
     ZoomIn = ul_sys::ULCursor_kCursor_ZoomIn as isize,
     ZoomOut = ul_sys::ULCursor_kCursor_ZoomOut as isize,
 }
+
+impl Cursor {
+    pub(crate) fn from_raw(cursor: ul_sys::ULCursor) -> Cursor {
+        match cursor {
+            ul_sys::ULCursor_kCursor_Alias => Cursor::Alias,
+            ul_sys::ULCursor_kCursor_Cell => Cursor::Cell,
+            ul_sys::ULCursor_kCursor_ColumnResize => Cursor::ColumnResize,
+            ul_sys::ULCursor_kCursor_ContextMenu => Cursor::ContextMenu,
+            ul_sys::ULCursor_kCursor_Copy => Cursor::Copy,
+            ul_sys::ULCursor_kCursor_Cross => Cursor::Cross,
+            ul_sys::ULCursor_kCursor_Custom => Cursor::Custom,
+            ul_sys::ULCursor_kCursor_EastPanning => Cursor::EastPanning,
+            ul_sys::ULCursor_kCursor_EastResize => Cursor::EastResize,
+            ul_sys::ULCursor_kCursor_EastWestResize => Cursor::EastWestResize,
+            ul_sys::ULCursor_kCursor_Grab => Cursor::Grab,
+            ul_sys::ULCursor_kCursor_Grabbing => Cursor::Grabbing,
+            ul_sys::ULCursor_kCursor_Hand => Cursor::Hand,
+            ul_sys::ULCursor_kCursor_Help => Cursor::Help,
+            ul_sys::ULCursor_kCursor_IBeam => Cursor::IBeam,
+            ul_sys::ULCursor_kCursor_MiddlePanning => Cursor::MiddlePanning,
+            ul_sys::ULCursor_kCursor_Move => Cursor::Move,
+            ul_sys::ULCursor_kCursor_NoDrop => Cursor::NoDrop,
+            ul_sys::ULCursor_kCursor_None => Cursor::None,
+            ul_sys::ULCursor_kCursor_NorthEastPanning => Cursor::NorthEastPanning,
+            ul_sys::ULCursor_kCursor_NorthEastResize => Cursor::NorthEastResize,
+            ul_sys::ULCursor_kCursor_NorthEastSouthWestResize => Cursor::NorthEastSouthWestResize,
+            ul_sys::ULCursor_kCursor_NorthPanning => Cursor::NorthPanning,
+            ul_sys::ULCursor_kCursor_NorthResize => Cursor::NorthResize,
+            ul_sys::ULCursor_kCursor_NorthSouthResize => Cursor::NorthSouthResize,
+            ul_sys::ULCursor_kCursor_NorthWestPanning => Cursor::NorthWestPanning,
+            ul_sys::ULCursor_kCursor_NorthWestResize => Cursor::NorthWestResize,
+            ul_sys::ULCursor_kCursor_NorthWestSouthEastResize => Cursor::NorthWestSouthEastResize,
+            ul_sys::ULCursor_kCursor_NotAllowed => Cursor::NotAllowed,
+            ul_sys::ULCursor_kCursor_Pointer => Cursor::Pointer,
+            ul_sys::ULCursor_kCursor_Progress => Cursor::Progress,
+            ul_sys::ULCursor_kCursor_RowResize => Cursor::RowResize,
+            ul_sys::ULCursor_kCursor_SouthEastPanning => Cursor::SouthEastPanning,
+            ul_sys::ULCursor_kCursor_SouthEastResize => Cursor::SouthEastResize,
+            ul_sys::ULCursor_kCursor_SouthPanning => Cursor::SouthPanning,
+            ul_sys::ULCursor_kCursor_SouthResize => Cursor::SouthResize,
+            ul_sys::ULCursor_kCursor_SouthWestPanning => Cursor::SouthWestPanning,
+            ul_sys::ULCursor_kCursor_SouthWestResize => Cursor::SouthWestResize,
+            ul_sys::ULCursor_kCursor_VerticalText => Cursor::VerticalText,
+            ul_sys::ULCursor_kCursor_Wait => Cursor::Wait,
+            ul_sys::ULCursor_kCursor_WestPanning => Cursor::WestPanning,
+            ul_sys::ULCursor_kCursor_WestResize => Cursor::WestResize,
+            ul_sys::ULCursor_kCursor_ZoomIn => Cursor::ZoomIn,
+            ul_sys::ULCursor_kCursor_ZoomOut => Cursor::ZoomOut,
+            _ => Cursor::Pointer,
+        }
+    }
+}
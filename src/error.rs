@@ -0,0 +1,146 @@
+use std::ffi::NulError;
+use std::fmt;
+use std::string::FromUtf16Error;
+
+use crate::jsc::{JSException, Protected};
+
+/// Errors returned by the high level Ultralight bindings.
+#[derive(Debug)]
+pub enum Error {
+    /// No view has been created or attached yet.
+    MissingView,
+    /// No window has been created yet.
+    MissingWindow,
+    /// No overlay has been created yet.
+    MissingOverlay,
+    /// The view has no CPU surface, eg. because the GPU renderer is used.
+    MissingSurface,
+    /// No element matches the given selector.
+    ElementNotFound(String),
+    /// The element matching the given selector has no visible area.
+    ElementNotVisible(String),
+    /// The document can't be scrolled far enough to capture it in tiles,
+    /// eg. because it has `overflow: hidden`.
+    NotScrollable,
+    /// A condition wasn't met within the given time.
+    Timeout(std::time::Duration),
+    /// A script threw an exception.
+    JSException {
+        message: String,
+        line: Option<u32>,
+        column: Option<u32>,
+        source_url: Option<String>,
+        stack: Option<String>,
+    },
+    /// A string passed to Ultralight contained an interior NUL byte.
+    InteriorNul(NulError),
+    /// A string returned by Ultralight was not valid UTF-16.
+    InvalidUtf16(FromUtf16Error),
+    /// A string could not be parsed as JSON.
+    InvalidJson,
+    /// The dimensions of a bitmap don't match its pixel buffer.
+    InvalidBitmapLayout(String),
+    /// The pixels of a bitmap could not be locked.
+    BitmapLockFailed,
+    /// A bitmap could not be written to the given PNG file.
+    PngWriteFailed(String),
+    /// A bitmap could not be converted or encoded by the `image` crate.
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingView => write!(f, "no view has been created"),
+            Error::MissingWindow => write!(f, "no window has been created"),
+            Error::MissingOverlay => write!(f, "no overlay has been created"),
+            Error::MissingSurface => write!(f, "view has no surface"),
+            Error::ElementNotFound(selector) => write!(f, "no element matches {}", selector),
+            Error::ElementNotVisible(selector) => write!(f, "element {} is not visible", selector),
+            Error::NotScrollable => write!(f, "document can't be scrolled to capture it"),
+            Error::Timeout(timeout) => write!(f, "timed out after {:?}", timeout),
+            Error::JSException { message, line, column, source_url, stack } => {
+                write!(f, "uncaught exception: {}", message)?;
+
+                match (source_url, line, column) {
+                    (Some(url), Some(line), Some(column)) => {
+                        write!(f, " ({}:{}:{})", url, line, column)?
+                    }
+                    (None, Some(line), Some(column)) => {
+                        write!(f, " (line {}, column {})", line, column)?
+                    }
+                    _ => {}
+                }
+
+                match stack {
+                    Some(stack) => write!(f, "\n{}", stack),
+                    None => Ok(()),
+                }
+            }
+            Error::InteriorNul(err) => write!(f, "string contains an interior NUL byte: {}", err),
+            Error::InvalidUtf16(err) => write!(f, "string is not valid UTF-16: {}", err),
+            Error::InvalidJson => write!(f, "string is not valid JSON"),
+            Error::InvalidBitmapLayout(reason) => write!(f, "invalid bitmap layout: {}", reason),
+            Error::BitmapLockFailed => write!(f, "failed to lock bitmap pixels"),
+            Error::PngWriteFailed(path) => write!(f, "failed to write PNG to {}", path),
+            #[cfg(feature = "image")]
+            Error::Image(err) => write!(f, "image error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InteriorNul(err) => Some(err),
+            Error::InvalidUtf16(err) => Some(err),
+            #[cfg(feature = "image")]
+            Error::Image(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(err: NulError) -> Self {
+        Error::InteriorNul(err)
+    }
+}
+
+impl From<FromUtf16Error> for Error {
+    fn from(err: FromUtf16Error) -> Self {
+        Error::InvalidUtf16(err)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::Image(err)
+    }
+}
+
+impl From<JSException> for Error {
+    fn from(exception: JSException) -> Self {
+        Error::from_exception(&exception)
+    }
+}
+
+impl<'a> From<Protected<'a, JSException>> for Error {
+    fn from(exception: Protected<'a, JSException>) -> Self {
+        Error::from_exception(&exception)
+    }
+}
+
+impl Error {
+    fn from_exception(exception: &JSException) -> Self {
+        Error::JSException {
+            message: exception.message(),
+            line: exception.line(),
+            column: exception.column(),
+            source_url: exception.source_url(),
+            stack: exception.stack(),
+        }
+    }
+}
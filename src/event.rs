@@ -0,0 +1,190 @@
+use crate::helpers_internal::ul_string;
+use crate::{Key, Modifiers};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum MouseEventType {
+    MouseMoved = ul_sys::ULMouseEventType_kMouseEventType_MouseMoved as isize,
+    MouseDown = ul_sys::ULMouseEventType_kMouseEventType_MouseDown as isize,
+    MouseUp = ul_sys::ULMouseEventType_kMouseEventType_MouseUp as isize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum MouseButton {
+    None = ul_sys::ULMouseButton_kMouseButton_None as isize,
+    Left = ul_sys::ULMouseButton_kMouseButton_Left as isize,
+    Middle = ul_sys::ULMouseButton_kMouseButton_Middle as isize,
+    Right = ul_sys::ULMouseButton_kMouseButton_Right as isize,
+}
+
+/// A mouse event that can be fired at a `View`, destroyed when dropped.
+///
+/// Coordinates are in pixels relative to the top-left corner of the view.
+pub struct MouseEvent {
+    raw: ul_sys::ULMouseEvent,
+}
+
+impl MouseEvent {
+    pub fn new(event_type: MouseEventType, x: i32, y: i32, button: MouseButton) -> MouseEvent {
+        unsafe {
+            MouseEvent {
+                raw: ul_sys::ulCreateMouseEvent(
+                    event_type as ul_sys::ULMouseEventType,
+                    x,
+                    y,
+                    button as ul_sys::ULMouseButton,
+                ),
+            }
+        }
+    }
+
+    pub fn raw(&self) -> ul_sys::ULMouseEvent {
+        self.raw
+    }
+}
+
+impl Drop for MouseEvent {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulDestroyMouseEvent(self.raw);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum KeyEventType {
+    /// Only here for compatibility with WebCore, use `RawKeyDown` for
+    /// physical key presses.
+    KeyDown = ul_sys::ULKeyEventType_kKeyEventType_KeyDown as isize,
+    KeyUp = ul_sys::ULKeyEventType_kKeyEventType_KeyUp as isize,
+    RawKeyDown = ul_sys::ULKeyEventType_kKeyEventType_RawKeyDown as isize,
+    /// Text input generated by a key press.
+    Char = ul_sys::ULKeyEventType_kKeyEventType_Char as isize,
+}
+
+/// A keyboard event that can be fired at a `View`, destroyed when dropped.
+pub struct KeyEvent {
+    raw: ul_sys::ULKeyEvent,
+}
+
+impl KeyEvent {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        event_type: KeyEventType,
+        modifiers: Modifiers,
+        virtual_key_code: i32,
+        native_key_code: i32,
+        text: &str,
+        unmodified_text: &str,
+        is_keypad: bool,
+        is_auto_repeat: bool,
+        is_system_key: bool,
+    ) -> KeyEvent {
+        unsafe {
+            // copied by ulCreateKeyEvent
+            let text = ul_string(text);
+            let unmodified_text = ul_string(unmodified_text);
+
+            KeyEvent {
+                raw: ul_sys::ulCreateKeyEvent(
+                    event_type as ul_sys::ULKeyEventType,
+                    modifiers.bits(),
+                    virtual_key_code,
+                    native_key_code,
+                    text.raw,
+                    unmodified_text.raw,
+                    is_keypad,
+                    is_auto_repeat,
+                    is_system_key,
+                ),
+            }
+        }
+    }
+
+    /// Creates a `RawKeyDown`, `KeyDown` or `KeyUp` event for `key`.
+    pub fn key(event_type: KeyEventType, key: Key, modifiers: Modifiers) -> KeyEvent {
+        let shift = modifiers.contains(Modifiers::SHIFT);
+
+        let text = key.text(shift).map(String::from).unwrap_or_default();
+        let unmodified_text = key.text(false).map(String::from).unwrap_or_default();
+
+        KeyEvent::new(
+            event_type,
+            modifiers,
+            key.virtual_key_code(),
+            0,
+            &text,
+            &unmodified_text,
+            false,
+            false,
+            false,
+        )
+    }
+
+    /// Creates a `Char` event inserting `text`.
+    pub fn char(text: &str, unmodified_text: &str, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent::new(
+            KeyEventType::Char,
+            modifiers,
+            0,
+            0,
+            text,
+            unmodified_text,
+            false,
+            false,
+            false,
+        )
+    }
+
+    pub fn raw(&self) -> ul_sys::ULKeyEvent {
+        self.raw
+    }
+}
+
+impl Drop for KeyEvent {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulDestroyKeyEvent(self.raw);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ScrollEventType {
+    ScrollByPixel = ul_sys::ULScrollEventType_kScrollEventType_ScrollByPixel as isize,
+    ScrollByPage = ul_sys::ULScrollEventType_kScrollEventType_ScrollByPage as isize,
+}
+
+/// A scroll (mouse wheel) event that can be fired at a `View`, destroyed
+/// when dropped.
+///
+/// The deltas are in pixels or pages depending on the event type, positive
+/// values scroll up and left.
+pub struct ScrollEvent {
+    raw: ul_sys::ULScrollEvent,
+}
+
+impl ScrollEvent {
+    pub fn new(event_type: ScrollEventType, delta_x: i32, delta_y: i32) -> ScrollEvent {
+        unsafe {
+            ScrollEvent {
+                raw: ul_sys::ulCreateScrollEvent(
+                    event_type as ul_sys::ULScrollEventType,
+                    delta_x,
+                    delta_y,
+                ),
+            }
+        }
+    }
+
+    pub fn raw(&self) -> ul_sys::ULScrollEvent {
+        self.raw
+    }
+}
+
+impl Drop for ScrollEvent {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulDestroyScrollEvent(self.raw);
+        }
+    }
+}
@@ -1,53 +1,42 @@
-use crate::helpers_internal::unpack_closure_hook_cb;
-
-pub fn create_js_function<T> (
-    view: crate::View,
-    name: &'static str,
-    mut hook: &mut T
-) -> ul_sys::JSObjectRef
-    where T: FnMut(
+use crate::jsc::{JSContext, JSException, JSObject, JSString, JSValue, Protected};
+use crate::Error;
+
+/// Creates a JavaScript function calling `hook` in the page of `view`.
+///
+/// The function object owns `hook`, which is dropped once the object is
+/// garbage collected. Fails with `Error::InteriorNul` if `name` contains a
+/// NUL byte.
+pub fn create_js_function<N, T> (
+    view: ul_sys::ULView,
+    name: N,
+    hook: T
+) -> Result<ul_sys::JSObjectRef, Error>
+    where N: AsRef<str>,
+          T: FnMut(
         ul_sys::JSContextRef,
         ul_sys::JSObjectRef,
         ul_sys::JSObjectRef,
         usize,
         *const ul_sys::JSValueRef,
         *mut ul_sys::JSValueRef,
-    ) -> ul_sys::JSValueRef
+    ) -> ul_sys::JSValueRef + 'static
 {
     unsafe {
-        let (
-            hook_closure,
-            hook_function
-        ) = unpack_closure_hook_cb(&mut hook);
-
-        let classname_str = std::ffi::CString::new(name).unwrap();
-
-        let jsclassdef = ul_sys::JSClassDefinition {
-            version: 0,
-            attributes: 0,
-            className: classname_str.as_ptr(),
-            parentClass: std::ptr::null_mut() as ul_sys::JSClassRef,
-            callAsFunction: Some(hook_function),
-            // need to implement drop!
-            //finalize: Some(|| std::mem::drop(jsclass)),
-        };
+        let (jsgctx, ..) = getJSContextFromView(view);
 
-        let jsclass = ul_sys::JSClassCreate(
-            &jsclassdef
-        );
+        // released when dropped at the end of the scope
+        let ctx = JSContext::from_context_ref(jsgctx);
 
-        let (jsgctx, ..) = getJSContextFromView(view);
+        let function = JSObject::new_function(&ctx, name.as_ref(), hook);
 
-        ul_sys::JSObjectMake(
-            jsgctx,
-            jsclass,
-            hook_closure
-        )
+        ul_sys::ulViewUnlockJSContext(view);
+
+        Ok(function?.raw)
     }
 }
 
 pub fn getJSContextFromView(
-    view: crate::View
+    view: ul_sys::ULView
 ) -> (ul_sys::JSContextRef, ul_sys::JSObjectRef) {
     unsafe {
         let jsgctx = ul_sys::ulViewLockJSContext(view);
@@ -57,55 +46,94 @@ pub fn getJSContextFromView(
     }
 }
 
-pub fn set_js_object_property(
-    view: crate::View,
-    name: &'static str,
+pub fn set_js_object_property<N>(
+    view: ul_sys::ULView,
+    name: N,
     object: ul_sys::JSObjectRef
-) {
+) -> Result<(), Error>
+    where N: Into<JSString>
+{
     unsafe {
         let (jsgctx, jsgctx_object) = getJSContextFromView(view);
 
-        let c_name = std::ffi::CString::new(
-            name
-        ).unwrap();
+        // released when dropped at the end of the scope
+        let propertyName: JSString = name.into();
 
-        let propertyName = ul_sys::JSStringCreateWithUTF8CString(
-            c_name.as_ptr()
-        );
+        let mut exception: ul_sys::JSValueRef = std::ptr::null_mut();
 
         ul_sys::JSObjectSetProperty(
             jsgctx,
             jsgctx_object,
-            propertyName,
+            propertyName.raw,
             object,
             0,
-            std::ptr::null_mut() as *mut *const ul_sys::OpaqueJSValue
+            &mut exception
         );
+
+        ul_sys::ulViewUnlockJSContext(view);
+
+        if !exception.is_null() {
+            return Err(JSException {
+                value: JSValue {
+                    raw: exception,
+                    ctx: jsgctx,
+                },
+            }.into());
+        }
     }
+
+    Ok(())
 }
 
 // "window.styla={callbacks:[{render:global_spotfire_hook}]};"
 
-pub fn evaluate_script(
-    view: crate::View,
-    script: &'static str
-) -> ul_sys::JSValueRef {
+/// Evaluates `script` in the page of `view`.
+///
+/// The caller picks the lifetime of the result, which must not outlive the
+/// renderer of `view`.
+pub fn evaluate_script<'a, S>(
+    view: ul_sys::ULView,
+    script: S,
+    source_url: Option<JSString>,
+    starting_line_number: i32
+) -> Result<Protected<'a, JSValue>, Protected<'a, JSException>>
+    where S: Into<JSString>
+{
     unsafe {
         let (jsgctx, jsgctx_object) = getJSContextFromView(view);
 
-        let script_c_str = std::ffi::CString::new(
-            script
-        ).unwrap();
+        // released when dropped at the end of the scope
+        let script: JSString = script.into();
 
-        ul_sys::JSEvaluateScript(
+        let mut exception: ul_sys::JSValueRef = std::ptr::null_mut();
+
+        let value = ul_sys::JSEvaluateScript(
             jsgctx,
-            ul_sys::JSStringCreateWithUTF8CString(
-                script_c_str.as_ptr()
-            ),
+            script.raw,
             jsgctx_object,
-            std::ptr::null_mut() as *mut ul_sys::OpaqueJSString,
-            ul_sys::kJSPropertyAttributeNone as i32,
-            std::ptr::null_mut() as *mut *const ul_sys::OpaqueJSValue
-        )
+            source_url.as_ref().map(|url| url.raw).unwrap_or(std::ptr::null_mut()),
+            starting_line_number,
+            &mut exception
+        );
+
+        // protected while the context is still locked, the caller uses the
+        // result after unlocking it
+        let result = if value.is_null() {
+            Err(Protected::new(JSException {
+                value: JSValue {
+                    raw: exception,
+                    ctx: jsgctx,
+                },
+            }))
+        } else {
+            Ok(Protected::new(JSValue {
+                raw: value,
+                ctx: jsgctx,
+            }))
+        };
+
+        ul_sys::ulViewUnlockJSContext(view);
+
+        result
     }
 }
@@ -1,26 +1,87 @@
-use crate::View;
+use crate::{ChildViewRequest, ConsoleMessage, Cursor, Error, LoadError, LoadEvent};
 
 use std::{
     os::raw::{
+        c_char,
+        c_int,
+        c_uint,
+        c_ulonglong,
         c_void
     },
 };
 
-pub unsafe fn ul_string(stref: &str) -> ul_sys::ULString {
-    let cstr = std::ffi::CString::new(
-        stref
-    ).unwrap();
+// Owned ULString, destroyed when dropped. Ultralight copies
+// strings passed to it, so this only has to outlive the call.
+pub struct ULStringGuard {
+    pub raw: ul_sys::ULString,
+}
 
-    ul_sys::ulCreateString(
-        cstr.as_ptr()
-    )
+impl Drop for ULStringGuard {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::ulDestroyString(self.raw);
+        }
+    }
+}
+
+pub unsafe fn ul_string(stref: &str) -> ULStringGuard {
+    ULStringGuard {
+        raw: ul_sys::ulCreateStringUTF8(
+            stref.as_ptr() as *const c_char,
+            stref.len() as _,
+        ),
+    }
+}
+
+// Copies a ULString into a Rust string. Strings returned by Ultralight
+// are owned by it, so this doesn't destroy `raw`.
+pub unsafe fn from_ul_string(raw: ul_sys::ULString) -> Result<String, Error> {
+    if raw.is_null() {
+        return Ok(String::new());
+    }
+
+    let length = ul_sys::ulStringGetLength(raw) as usize;
+
+    if length == 0 {
+        return Ok(String::new());
+    }
+
+    Ok(String::from_utf16(std::slice::from_raw_parts(
+        ul_sys::ulStringGetData(raw) as *const u16,
+        length,
+    ))?)
 }
 
-pub unsafe fn unpack_window_close_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void))
+// Quotes a string as a JavaScript string literal, for splicing
+// user input (eg. selectors) into evaluated scripts.
+pub fn js_string_literal(s: &str) -> String {
+    let mut literal = String::with_capacity(s.len() + 2);
+
+    literal.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\u{2028}' => literal.push_str("\\u2028"),
+            '\u{2029}' => literal.push_str("\\u2029"),
+            c if (c as u32) < 0x20 => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+
+    literal.push('"');
+
+    literal
+}
+
+pub unsafe fn unpack_window_close_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, ul_sys::ULWindow))
     where
         F: FnMut(),
 {
-    extern "C" fn trampoline<F>(data: *mut c_void)
+    extern "C" fn trampoline<F>(data: *mut c_void, _window: ul_sys::ULWindow)
         where
             F: FnMut(),
     {
@@ -31,11 +92,11 @@ pub unsafe fn unpack_window_close_cb<F>(closure: &mut F) -> (*mut c_void, unsafe
     (closure as *mut F as *mut c_void, trampoline::<F>)
 }
 
-pub unsafe fn unpack_window_resize_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, width: u32, height: u32))
+pub unsafe fn unpack_window_resize_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, ul_sys::ULWindow, width: u32, height: u32))
     where
         F: FnMut(u32, u32),
 {
-    extern "C" fn trampoline<F>(data: *mut c_void, width: u32, height: u32)
+    extern "C" fn trampoline<F>(data: *mut c_void, _window: ul_sys::ULWindow, width: u32, height: u32)
         where
             F: FnMut(u32, u32),
     {
@@ -46,15 +107,15 @@ pub unsafe fn unpack_window_resize_cb<F>(closure: &mut F) -> (*mut c_void, unsaf
     (closure as *mut F as *mut c_void, trampoline::<F>)
 }
 
-// All callbacks that accept take a (view: ULView) argument
+// Callbacks that only take a (view: ULView) argument (update history)
 
-pub unsafe fn unpack_closure_view_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, View))
+pub unsafe fn unpack_closure_view_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, ul_sys::ULView))
     where
-        F: FnMut(View),
+        F: FnMut(ul_sys::ULView),
 {
-    extern "C" fn trampoline<F>(data: *mut c_void, n: View)
+    extern "C" fn trampoline<F>(data: *mut c_void, n: ul_sys::ULView)
         where
-            F: FnMut(View),
+            F: FnMut(ul_sys::ULView),
     {
         let closure: &mut F = unsafe { &mut *(data as *mut F) };
         (*closure)(n);
@@ -63,17 +124,193 @@ pub unsafe fn unpack_closure_view_cb<F>(closure: &mut F) -> (*mut c_void, unsafe
     (closure as *mut F as *mut c_void, trampoline::<F>)
 }
 
+type LoadCallbackSig = unsafe extern "C" fn(
+    *mut c_void,
+    ul_sys::ULView,
+    c_ulonglong,
+    bool,
+    ul_sys::ULString,
+);
+
+// Load callbacks (finish loading, DOM ready) only passing the view on
+
+pub unsafe fn unpack_closure_view_load_cb<F>(closure: &mut F) -> (*mut c_void, LoadCallbackSig)
+    where
+        F: FnMut(ul_sys::ULView),
+{
+    unsafe extern "C" fn trampoline<F>(
+        data: *mut c_void,
+        caller: ul_sys::ULView,
+        _frame_id: c_ulonglong,
+        _is_main_frame: bool,
+        _url: ul_sys::ULString,
+    )
+        where
+            F: FnMut(ul_sys::ULView),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+        (*closure)(caller);
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
+// Load callbacks (begin loading, window object ready) with a LoadEvent
+
+pub unsafe fn unpack_closure_load_cb<F>(closure: &mut F) -> (*mut c_void, LoadCallbackSig)
+    where
+        F: FnMut(ul_sys::ULView, LoadEvent),
+{
+    unsafe extern "C" fn trampoline<F>(
+        data: *mut c_void,
+        caller: ul_sys::ULView,
+        frame_id: c_ulonglong,
+        is_main_frame: bool,
+        url: ul_sys::ULString,
+    )
+        where
+            F: FnMut(ul_sys::ULView, LoadEvent),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+        (*closure)(caller, LoadEvent::from_raw(frame_id, is_main_frame, url));
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
+type FailLoadingCallbackSig = unsafe extern "C" fn(
+    *mut c_void,
+    ul_sys::ULView,
+    c_ulonglong,
+    bool,
+    ul_sys::ULString,
+    ul_sys::ULString,
+    ul_sys::ULString,
+    c_int,
+);
+
+pub unsafe fn unpack_closure_fail_loading_cb<F>(closure: &mut F) -> (*mut c_void, FailLoadingCallbackSig)
+    where
+        F: FnMut(ul_sys::ULView, LoadError),
+{
+    unsafe extern "C" fn trampoline<F>(
+        data: *mut c_void,
+        caller: ul_sys::ULView,
+        frame_id: c_ulonglong,
+        is_main_frame: bool,
+        url: ul_sys::ULString,
+        description: ul_sys::ULString,
+        error_domain: ul_sys::ULString,
+        error_code: c_int,
+    )
+        where
+            F: FnMut(ul_sys::ULView, LoadError),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+        (*closure)(caller, LoadError {
+            frame_id,
+            is_main_frame,
+            url: from_ul_string(url).unwrap_or_default(),
+            description: from_ul_string(description).unwrap_or_default(),
+            error_domain: from_ul_string(error_domain).unwrap_or_default(),
+            error_code,
+        });
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
+// Change title, URL and tooltip callbacks
+
+pub unsafe fn unpack_closure_string_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, ul_sys::ULView, ul_sys::ULString))
+    where
+        F: FnMut(ul_sys::ULView, String),
+{
+    unsafe extern "C" fn trampoline<F>(data: *mut c_void, caller: ul_sys::ULView, string: ul_sys::ULString)
+        where
+            F: FnMut(ul_sys::ULView, String),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+        (*closure)(caller, from_ul_string(string).unwrap_or_default());
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
+pub unsafe fn unpack_closure_cursor_cb<F>(closure: &mut F) -> (*mut c_void, unsafe extern "C" fn(*mut c_void, ul_sys::ULView, ul_sys::ULCursor))
+    where
+        F: FnMut(ul_sys::ULView, Cursor),
+{
+    unsafe extern "C" fn trampoline<F>(data: *mut c_void, caller: ul_sys::ULView, cursor: ul_sys::ULCursor)
+        where
+            F: FnMut(ul_sys::ULView, Cursor),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+        (*closure)(caller, Cursor::from_raw(cursor));
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
+type CreateChildViewCallbackSig = unsafe extern "C" fn(
+    *mut c_void,
+    ul_sys::ULView,
+    ul_sys::ULString,
+    ul_sys::ULString,
+    bool,
+    ul_sys::ULIntRect,
+) -> ul_sys::ULView;
+
+pub unsafe fn unpack_closure_create_child_view_cb<F>(closure: &mut F) -> (*mut c_void, CreateChildViewCallbackSig)
+    where
+        F: FnMut(ul_sys::ULView, ChildViewRequest) -> Option<ul_sys::ULView>,
+{
+    unsafe extern "C" fn trampoline<F>(
+        data: *mut c_void,
+        caller: ul_sys::ULView,
+        opener_url: ul_sys::ULString,
+        target_url: ul_sys::ULString,
+        is_popup: bool,
+        popup_rect: ul_sys::ULIntRect,
+    ) -> ul_sys::ULView
+        where
+            F: FnMut(ul_sys::ULView, ChildViewRequest) -> Option<ul_sys::ULView>,
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+
+        let request = ChildViewRequest {
+            opener_url: from_ul_string(opener_url).unwrap_or_default(),
+            target_url: from_ul_string(target_url).unwrap_or_default(),
+            is_popup,
+            popup_rect: popup_rect.into(),
+        };
+
+        (*closure)(caller, request).unwrap_or(std::ptr::null_mut())
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
+}
+
 // JSContextHooks
 type ClosureHookCallbackSig = unsafe extern "C" fn(
     ul_sys::JSContextRef,
     ul_sys::JSObjectRef,
     ul_sys::JSObjectRef,
-    usize,
+    ul_sys::size_t,
     *const ul_sys::JSValueRef,
     *mut ul_sys::JSValueRef
 ) -> ul_sys::JSValueRef;
 
-pub unsafe fn unpack_closure_hook_cb<F>(closure: &mut F) -> (*mut c_void, ClosureHookCallbackSig)
+type ClosureHookFinalizeSig = unsafe extern "C" fn(ul_sys::JSObjectRef);
+
+/// Unpacks a closure backing a JavaScript function object.
+///
+/// The closure is leaked into the returned pointer, which has to be set as
+/// the private data of the object; the returned finalizer drops it again
+/// once the object is garbage collected.
+pub unsafe fn unpack_closure_hook_cb<F>(
+    closure: Box<F>,
+) -> (*mut c_void, ClosureHookCallbackSig, ClosureHookFinalizeSig)
     where
         F: FnMut(
             ul_sys::JSContextRef,
@@ -88,7 +325,7 @@ pub unsafe fn unpack_closure_hook_cb<F>(closure: &mut F) -> (*mut c_void, Closur
         ctx: ul_sys::JSContextRef,
         function: ul_sys::JSObjectRef,
         thisObject: ul_sys::JSObjectRef,
-        argumentCount: usize,
+        argumentCount: ul_sys::size_t,
         arguments: *const ul_sys::JSValueRef,
         exception: *mut ul_sys::JSValueRef,
     ) -> ul_sys::JSValueRef
@@ -108,69 +345,62 @@ pub unsafe fn unpack_closure_hook_cb<F>(closure: &mut F) -> (*mut c_void, Closur
             ctx,
             function,
             thisObject,
-            argumentCount,
+            argumentCount as usize,
             arguments,
             exception
         )
     }
 
-    (closure as *mut F as *mut c_void, trampoline::<F>)
+    unsafe extern "C" fn finalize<F>(object: ul_sys::JSObjectRef) {
+        let closure = ul_sys::JSObjectGetPrivate(object) as *mut F;
+
+        if !closure.is_null() {
+            drop(Box::from_raw(closure));
+        }
+    }
+
+    (Box::into_raw(closure) as *mut c_void, trampoline::<F>, finalize::<F>)
 }
 
-static msg_parsing_failed: &'static str = "!parsing failed!";
-
-pub unsafe extern "C" fn log_forward_cb(
-    user_data: *mut ::std::os::raw::c_void,
-    caller: View,
-    source: ul_sys::ULMessageSource,           /* u32 */
-    level: ul_sys::ULMessageLevel,             /* u32 */
-    message: ul_sys::ULString,                 /* *mut C_String aka *mut u8 */
-    line_number: ::std::os::raw::c_uint,    /* u32 */
-    column_number: ::std::os::raw::c_uint,  /* u32 */
-    source_id: ul_sys::ULString,               /* *mut C_String aka *mut u8 */
-) {
-    let level = match level {
-        ul_sys::ULMessageLevel_kMessageLevel_Log => "log",
-        ul_sys::ULMessageLevel_kMessageLevel_Warning => "warning",
-        ul_sys::ULMessageLevel_kMessageLevel_Error => "error",
-        ul_sys::ULMessageLevel_kMessageLevel_Debug => "debug",
-        ul_sys::ULMessageLevel_kMessageLevel_Info => "info",
-        _ => "unknown",
-    };
-
-    let source = match source {
-        ul_sys::ULMessageSource_kMessageSource_XML => "xml",
-        ul_sys::ULMessageSource_kMessageSource_JS => "js",
-        ul_sys::ULMessageSource_kMessageSource_Network => "network",
-        ul_sys::ULMessageSource_kMessageSource_ConsoleAPI => "consoleapi",
-        ul_sys::ULMessageSource_kMessageSource_Storage => "storage",
-        ul_sys::ULMessageSource_kMessageSource_AppCache => "appcache",
-        ul_sys::ULMessageSource_kMessageSource_Rendering => "rendering",
-        ul_sys::ULMessageSource_kMessageSource_CSS => "css",
-        ul_sys::ULMessageSource_kMessageSource_Security => "security",
-        ul_sys::ULMessageSource_kMessageSource_ContentBlocker => "contentblocker",
-        ul_sys::ULMessageSource_kMessageSource_Other => "other",
-        _ => "unknown",
-    };
-
-    let message = match String::from_utf16(std::slice::from_raw_parts_mut(
-        ul_sys::ulStringGetData(message),
-        ul_sys::ulStringGetLength(message) as usize,
-    )) {
-        Ok(msg) => msg,
-        Err(_) => msg_parsing_failed.to_string(),
-    };
-
-    let source_id = match String::from_utf16(std::slice::from_raw_parts_mut(
-        ul_sys::ulStringGetData(source_id),
-        ul_sys::ulStringGetLength(source_id) as usize,
-    )) {
-        Ok(src) => src,
-        Err(_) => msg_parsing_failed.to_string(),
-    };
-
-    println!(
-        "[{}] [{}] {} ({}:{}:{})",
-        level, source, message, source_id, line_number, column_number
-    );
+type ConsoleMessageCallbackSig = unsafe extern "C" fn(
+    *mut c_void,
+    ul_sys::ULView,
+    ul_sys::ULMessageSource,
+    ul_sys::ULMessageLevel,
+    ul_sys::ULString,
+    c_uint,
+    c_uint,
+    ul_sys::ULString,
+);
+
+pub unsafe fn unpack_closure_console_cb<F>(closure: &mut F) -> (*mut c_void, ConsoleMessageCallbackSig)
+    where
+        F: FnMut(ul_sys::ULView, ConsoleMessage),
+{
+    unsafe extern "C" fn trampoline<F>(
+        data: *mut c_void,
+        caller: ul_sys::ULView,
+        source: ul_sys::ULMessageSource,
+        level: ul_sys::ULMessageLevel,
+        message: ul_sys::ULString,
+        line_number: c_uint,
+        column_number: c_uint,
+        source_id: ul_sys::ULString,
+    )
+        where
+            F: FnMut(ul_sys::ULView, ConsoleMessage),
+    {
+        let closure: &mut F = &mut *(data as *mut F);
+
+        (*closure)(caller, ConsoleMessage::from_raw(
+            source,
+            level,
+            message,
+            line_number,
+            column_number,
+            source_id,
+        ));
+    }
+
+    (closure as *mut F as *mut c_void, trampoline::<F>)
 }
@@ -39,6 +39,18 @@ impl JSContext {
         }
     }
 
+    /// Wraps the global context of `ctx`, eg. the context passed to a
+    /// native callback, retaining it for the lifetime of the wrapper.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` has to be a valid context.
+    pub unsafe fn from_context_ref(ctx: ul_sys::JSContextRef) -> Self {
+        JSContext {
+            raw: ul_sys::JSGlobalContextRetain(ul_sys::JSContextGetGlobalContext(ctx)),
+        }
+    }
+
     /// Gets the context group to which a JavaScript execution context belongs.
     pub fn group(&self) -> JSContextGroup {
         let g = unsafe { ul_sys::JSContextGetGroup(self.raw) };
@@ -0,0 +1,337 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{JSContext, JSObject, JSValue};
+use std::fmt::Display;
+
+/// Conversion of a JavaScript value into a Rust value, used for the
+/// arguments of functions bound through `JSFunction`.
+///
+/// Conversions are strict: a number is only accepted for numeric types and
+/// a string only for `String`. Use `Option<T>` to accept `undefined` and
+/// `null` (and missing arguments), or `JSValue` to accept anything.
+pub trait FromJSValue: Sized {
+    /// Converts `value`, or returns a message describing why it couldn't.
+    fn from_js_value(value: &JSValue) -> Result<Self, String>;
+}
+
+/// Conversion of a Rust value into a JavaScript value, used for the return
+/// values of functions bound through `JSFunction`.
+pub trait IntoJSValue {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue;
+}
+
+/// Return values of functions bound through `JSFunction`: either a plain
+/// `IntoJSValue`, or a `Result` whose error is thrown as an exception.
+pub trait IntoJSResult {
+    fn into_js_result(self, ctx: &JSContext) -> Result<JSValue, String>;
+}
+
+/// Rust closures callable from JavaScript, with their arguments converted
+/// through `FromJSValue` and their return value through `IntoJSResult`.
+///
+/// Implemented for closures taking up to eight arguments. Missing arguments
+/// are passed as `undefined`, extra ones are ignored.
+///
+/// ```
+/// # use javascriptcore::*;
+/// let ctx = JSContext::default();
+///
+/// let mut add = |a: f64, b: f64| a + b;
+///
+/// let args = [JSValue::new_number(&ctx, 1.0), JSValue::new_number(&ctx, 2.0)];
+/// let sum = add.call_with(&ctx, &args).unwrap();
+/// assert_eq!(sum.as_number().unwrap(), 3.0);
+/// ```
+pub trait JSFunction<Args> {
+    /// Converts `args`, calls the function and converts its return value.
+    ///
+    /// Returns the message of the exception to throw if an argument could
+    /// not be converted or the function returned an `Err`.
+    fn call_with(&mut self, ctx: &JSContext, args: &[JSValue]) -> Result<JSValue, String>;
+}
+
+fn expected(what: &str, value: &JSValue) -> String {
+    format!("expected {}, got {:?}", what, value.get_type())
+}
+
+impl FromJSValue for JSValue {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        Ok(JSValue {
+            raw: value.raw,
+            ctx: value.ctx,
+        })
+    }
+}
+
+impl FromJSValue for bool {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        if value.is_boolean() {
+            Ok(value.as_boolean())
+        } else {
+            Err(expected("a boolean", value))
+        }
+    }
+}
+
+impl FromJSValue for f64 {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        if value.is_number() {
+            // only fails for NaN, which is a number all the same
+            Ok(value.as_number().unwrap_or(f64::NAN))
+        } else {
+            Err(expected("a number", value))
+        }
+    }
+}
+
+macro_rules! from_js_number {
+    ($($t:ty),*) => {
+        $(
+            impl FromJSValue for $t {
+                fn from_js_value(value: &JSValue) -> Result<Self, String> {
+                    let n = f64::from_js_value(value)?;
+
+                    // MAX rounds up to the next power of two as f64 for the
+                    // 64 bit types, so compare against that power exclusively
+                    let end = (<$t>::MAX / 2 + 1) as f64 * 2.0;
+
+                    if n.fract() == 0.0 && n >= <$t>::MIN as f64 && n < end {
+                        Ok(n as $t)
+                    } else {
+                        Err(format!("expected {}, got {}", stringify!($t), n))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_js_number!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromJSValue for f32 {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        f64::from_js_value(value).map(|n| n as f32)
+    }
+}
+
+impl FromJSValue for String {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        if value.is_string() {
+            value
+                .as_string()
+                .map(|s| s.to_string())
+                .map_err(|e| e.message())
+        } else {
+            Err(expected("a string", value))
+        }
+    }
+}
+
+impl<T: FromJSValue> FromJSValue for Option<T> {
+    fn from_js_value(value: &JSValue) -> Result<Self, String> {
+        if value.is_undefined() || value.is_null() {
+            Ok(None)
+        } else {
+            T::from_js_value(value).map(Some)
+        }
+    }
+}
+
+impl IntoJSValue for JSValue {
+    fn into_js_value(self, _ctx: &JSContext) -> JSValue {
+        self
+    }
+}
+
+impl IntoJSValue for JSObject {
+    fn into_js_value(self, _ctx: &JSContext) -> JSValue {
+        self.value
+    }
+}
+
+impl IntoJSValue for () {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        JSValue::new_undefined(ctx)
+    }
+}
+
+impl IntoJSValue for bool {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        JSValue::new_boolean(ctx, self)
+    }
+}
+
+macro_rules! into_js_number {
+    ($($t:ty),*) => {
+        $(
+            impl IntoJSValue for $t {
+                fn into_js_value(self, ctx: &JSContext) -> JSValue {
+                    JSValue::new_number(ctx, self as f64)
+                }
+            }
+        )*
+    };
+}
+
+into_js_number!(i8, i16, i32, i64, u8, u16, u32, u64, f32);
+
+impl IntoJSValue for f64 {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        JSValue::new_number(ctx, self)
+    }
+}
+
+impl IntoJSValue for String {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        JSValue::new_string(ctx, self)
+    }
+}
+
+impl<'s> IntoJSValue for &'s str {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        JSValue::new_string(ctx, self)
+    }
+}
+
+impl<T: IntoJSValue> IntoJSValue for Option<T> {
+    fn into_js_value(self, ctx: &JSContext) -> JSValue {
+        match self {
+            Some(value) => value.into_js_value(ctx),
+            None => JSValue::new_null(ctx),
+        }
+    }
+}
+
+impl<T: IntoJSValue> IntoJSResult for T {
+    fn into_js_result(self, ctx: &JSContext) -> Result<JSValue, String> {
+        Ok(self.into_js_value(ctx))
+    }
+}
+
+impl<T: IntoJSValue, E: Display> IntoJSResult for Result<T, E> {
+    fn into_js_result(self, ctx: &JSContext) -> Result<JSValue, String> {
+        self.map(|value| value.into_js_value(ctx))
+            .map_err(|e| e.to_string())
+    }
+}
+
+macro_rules! js_function {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg,)*> JSFunction<($($arg,)*)> for F
+            where
+                F: FnMut($($arg),*) -> R,
+                R: IntoJSResult,
+                $($arg: FromJSValue,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables, unused_assignments)]
+            fn call_with(&mut self, ctx: &JSContext, args: &[JSValue]) -> Result<JSValue, String> {
+                let mut args = args.iter();
+                let mut index = 0;
+
+                $(
+                    index += 1;
+
+                    let $arg = match args.next() {
+                        Some(value) => <$arg as FromJSValue>::from_js_value(value),
+                        None => <$arg as FromJSValue>::from_js_value(&JSValue::new_undefined(ctx)),
+                    }
+                    .map_err(|e| format!("argument {}: {}", index, e))?;
+                )*
+
+                (self)($($arg),*).into_js_result(ctx)
+            }
+        }
+    };
+}
+
+js_function!();
+js_function!(A);
+js_function!(A, B);
+js_function!(A, B, C);
+js_function!(A, B, C, D);
+js_function!(A, B, C, D, E);
+js_function!(A, B, C, D, E, G);
+js_function!(A, B, C, D, E, G, H);
+js_function!(A, B, C, D, E, G, H, I);
+
+#[cfg(test)]
+mod tests {
+    use super::super::{JSContext, JSValue};
+    use super::{FromJSValue, IntoJSValue, JSFunction};
+
+    #[test]
+    fn from_js_value() {
+        let ctx = JSContext::default();
+
+        let n = JSValue::new_number(&ctx, 3.0);
+        assert_eq!(f64::from_js_value(&n), Ok(3.0));
+        assert_eq!(u8::from_js_value(&n), Ok(3));
+        assert!(String::from_js_value(&n).is_err());
+        assert!(u8::from_js_value(&JSValue::new_number(&ctx, 3.5)).is_err());
+        assert!(u8::from_js_value(&JSValue::new_number(&ctx, 300.0)).is_err());
+        assert_eq!(u8::from_js_value(&JSValue::new_number(&ctx, 255.0)), Ok(255));
+        assert!(u8::from_js_value(&JSValue::new_number(&ctx, 256.0)).is_err());
+        assert_eq!(i8::from_js_value(&JSValue::new_number(&ctx, -128.0)), Ok(-128));
+        assert!(i8::from_js_value(&JSValue::new_number(&ctx, 128.0)).is_err());
+
+        let two_pow_63 = JSValue::new_number(&ctx, 9223372036854775808.0);
+        assert!(i64::from_js_value(&two_pow_63).is_err());
+        assert_eq!(u64::from_js_value(&two_pow_63), Ok(1 << 63));
+        assert!(u64::from_js_value(&JSValue::new_number(&ctx, 18446744073709551616.0)).is_err());
+        assert_eq!(
+            i64::from_js_value(&JSValue::new_number(&ctx, -9223372036854775808.0)),
+            Ok(i64::MIN)
+        );
+
+        let s = JSValue::new_string(&ctx, "abc");
+        assert_eq!(String::from_js_value(&s), Ok("abc".to_owned()));
+        assert!(bool::from_js_value(&s).is_err());
+
+        let u = JSValue::new_undefined(&ctx);
+        assert_eq!(Option::<f64>::from_js_value(&u), Ok(None));
+        assert_eq!(Option::<f64>::from_js_value(&n), Ok(Some(3.0)));
+    }
+
+    #[test]
+    fn into_js_value() {
+        let ctx = JSContext::default();
+
+        assert!(().into_js_value(&ctx).is_undefined());
+        assert!(true.into_js_value(&ctx).as_boolean());
+        assert_eq!(7u32.into_js_value(&ctx).as_number().unwrap(), 7.0);
+        assert_eq!("abc".into_js_value(&ctx).as_string().unwrap(), "abc");
+        assert!(None::<f64>.into_js_value(&ctx).is_null());
+    }
+
+    #[test]
+    fn call_with() {
+        let ctx = JSContext::default();
+
+        let mut f = |a: f64, b: String| -> Result<bool, String> {
+            if b.is_empty() {
+                Err("empty".to_owned())
+            } else {
+                Ok(a > 1.0)
+            }
+        };
+
+        let args = [JSValue::new_number(&ctx, 2.0), JSValue::new_string(&ctx, "x")];
+        assert!(f.call_with(&ctx, &args).unwrap().as_boolean());
+
+        let args = [JSValue::new_number(&ctx, 2.0), JSValue::new_string(&ctx, "")];
+        assert_eq!(f.call_with(&ctx, &args).unwrap_err(), "empty");
+
+        let args = [JSValue::new_string(&ctx, "2")];
+        assert!(f.call_with(&ctx, &args).unwrap_err().starts_with("argument 1:"));
+
+        let args = [JSValue::new_number(&ctx, 2.0)];
+        assert!(f.call_with(&ctx, &args).unwrap_err().starts_with("argument 2:"));
+
+        let mut g = |a: Option<f64>| a.unwrap_or(-1.0);
+        assert_eq!(g.call_with(&ctx, &[]).unwrap().as_number().unwrap(), -1.0);
+    }
+}
@@ -4,6 +4,137 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::JSException;
+use super::{JSException, JSValue};
+use std::error::Error;
+use std::fmt;
 
-impl JSException {}
+impl JSException {
+    /// Gets the message of the exception.
+    ///
+    /// If the thrown value is an `Error` object, this is its `message`
+    /// property, otherwise the thrown value converted to a string.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    ///
+    /// let e = evaluate_script(&ctx, "throw new Error('abc')", None, "test.js", 1).unwrap_err();
+    /// assert_eq!(e.message(), "abc");
+    /// ```
+    pub fn message(&self) -> String {
+        if self.value.raw.is_null() {
+            return "unknown exception".to_owned();
+        }
+
+        if self.value.is_object() {
+            if let Ok(o) = self.value.as_object() {
+                if let Ok(m) = o.get_property("message") {
+                    if m.is_string() {
+                        if let Ok(s) = m.as_string() {
+                            return s.to_string();
+                        }
+                    }
+                }
+            }
+        }
+
+        match self.value.as_string() {
+            Ok(s) => s.to_string(),
+            Err(_) => "unknown exception".to_owned(),
+        }
+    }
+
+    /// Gets the stack trace of the exception, if the thrown value has one.
+    pub fn stack(&self) -> Option<String> {
+        let s = self.property("stack")?;
+        if s.is_string() {
+            s.as_string().ok().map(|s| s.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Gets the line number the exception was thrown at, if known.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    ///
+    /// let e = evaluate_script(&ctx, "\n\nthrow new Error('abc')", None, "test.js", 1).unwrap_err();
+    /// assert_eq!(e.line(), Some(3));
+    /// ```
+    pub fn line(&self) -> Option<u32> {
+        self.number_property("line")
+    }
+
+    /// Gets the column number the exception was thrown at, if known.
+    pub fn column(&self) -> Option<u32> {
+        self.number_property("column")
+    }
+
+    /// Gets the URL of the script the exception was thrown in, if known.
+    pub fn source_url(&self) -> Option<String> {
+        let s = self.property("sourceURL")?;
+        if s.is_string() {
+            s.as_string().ok().map(|s| s.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn property(&self, name: &str) -> Option<JSValue> {
+        if self.value.raw.is_null() || !self.value.is_object() {
+            return None;
+        }
+
+        self.value.as_object().ok()?.get_property(name).ok()
+    }
+
+    fn number_property(&self, name: &str) -> Option<u32> {
+        let n = self.property(name)?;
+        if n.is_number() {
+            n.as_number().ok().map(|n| n as u32)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for JSException {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.message())
+    }
+}
+
+impl Error for JSException {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{evaluate_script, JSContext};
+
+    #[test]
+    fn exception_message() {
+        let ctx = JSContext::default();
+
+        let e = evaluate_script(&ctx, "throw new Error('abc')", None, "test.js", 1).unwrap_err();
+        assert_eq!(e.message(), "abc");
+        assert!(e.stack().is_some());
+
+        let e = evaluate_script(&ctx, "throw 'def'", None, "test.js", 1).unwrap_err();
+        assert_eq!(e.message(), "def");
+        assert!(e.stack().is_none());
+    }
+
+    #[test]
+    fn exception_location() {
+        let ctx = JSContext::default();
+
+        let e = evaluate_script(&ctx, "\n\nthrow new Error('abc')", None, "test.js", 1).unwrap_err();
+        assert_eq!(e.line(), Some(3));
+        assert!(e.column().is_some());
+        assert_eq!(e.source_url(), Some("test.js".to_owned()));
+
+        let e = evaluate_script(&ctx, "throw 'def'", None, "test.js", 1).unwrap_err();
+        assert!(e.line().is_none());
+    }
+}
@@ -15,12 +15,29 @@ mod base;
 mod class;
 mod context;
 mod contextgroup;
+mod convert;
 mod exception;
 mod object;
+mod promise;
+mod protected;
+#[cfg(feature = "serde")]
+mod serialization;
 mod string;
+mod typed_array;
 mod value;
 
 pub use base::{check_script_syntax, evaluate_script, garbage_collect};
+pub use convert::{FromJSValue, IntoJSResult, IntoJSValue, JSFunction};
+pub use object::JSPropertyAttributes;
+pub(crate) use promise::discard_all_pending_promises;
+pub use promise::{
+    discard_pending_promises, promise_channel, settle_pending_promises, PendingPromise,
+    PromiseSender, Resolver,
+};
+pub use protected::{AsJSValue, Protected};
+#[cfg(feature = "serde")]
+pub use serialization::{from_value, to_value, SerdeError};
+pub use typed_array::TypedArrayElement;
 pub use ul_sys::{JSType, JSTypedArrayType};
 
 /// A JavaScript class.
@@ -4,11 +4,125 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::{JSObject, JSString, JSValue};
-use std::ops::Deref;
-use std::ptr;
+use super::{JSClass, JSContext, JSException, JSObject, JSString, JSValue};
+use crate::helpers_internal::unpack_closure_hook_cb;
+use crate::Error;
+use std::ffi::CString;
+use std::ops::{BitOr, BitOrAssign, Deref};
+use std::{mem, ptr};
 
 impl JSObject {
+    /// Creates an empty object.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject};
+    /// let ctx = JSContext::default();
+    /// let o = JSObject::new(&ctx);
+    ///
+    /// assert!(o.is_object());
+    /// assert_eq!(o.property_names().count(), 0);
+    /// ```
+    pub fn new(ctx: &JSContext) -> Self {
+        let raw = unsafe { ul_sys::JSObjectMake(ctx.raw, ptr::null_mut(), ptr::null_mut()) };
+
+        JSObject {
+            raw,
+            value: JSValue {
+                raw,
+                ctx: ctx.raw,
+            },
+        }
+    }
+
+    /// Creates an array holding `items`.
+    ///
+    /// Returns either the array, or the exception that was thrown.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSValue};
+    /// let ctx = JSContext::default();
+    /// let a = JSObject::new_array(&ctx, &[
+    ///     JSValue::new_number(&ctx, 3.0),
+    ///     JSValue::new_boolean(&ctx, true),
+    /// ]).expect("array");
+    ///
+    /// assert!(a.is_array());
+    /// assert_eq!(a.get_property_at_index(0).expect("value").as_number().expect("number"), 3.0);
+    /// ```
+    pub fn new_array(ctx: &JSContext, items: &[JSValue]) -> Result<Self, JSException> {
+        let items: Vec<ul_sys::JSValueRef> = items.iter().map(|item| item.raw).collect();
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let raw = unsafe { ul_sys::JSObjectMakeArray(ctx.raw, items.len() as _, items.as_ptr(), &mut e) };
+        if raw.is_null() {
+            Err(JSException {
+                value: JSValue {
+                    raw: e,
+                    ctx: ctx.raw,
+                },
+            })
+        } else {
+            Ok(JSObject {
+                raw,
+                value: JSValue {
+                    raw,
+                    ctx: ctx.raw,
+                },
+            })
+        }
+    }
+
+    /// Creates a function object calling `callback` with the raw arguments
+    /// of `JSObjectCallAsFunctionCallback`.
+    ///
+    /// * `ctx`: The execution context to use.
+    /// * `name`: The class name of the function object, used for debugging.
+    ///
+    /// The object owns `callback`, which is dropped when the object is
+    /// garbage collected.
+    ///
+    /// Returns `Error::InteriorNul` if `name` contains a NUL byte.
+    pub fn new_function<F>(ctx: &JSContext, name: &str, callback: F) -> Result<Self, Error>
+        where
+            F: FnMut(
+                ul_sys::JSContextRef,
+                ul_sys::JSObjectRef,
+                ul_sys::JSObjectRef,
+                usize,
+                *const ul_sys::JSValueRef,
+                *mut ul_sys::JSValueRef,
+            ) -> ul_sys::JSValueRef + 'static,
+    {
+        let class_name = CString::new(name)?;
+
+        unsafe {
+            let (data, call, finalize) = unpack_closure_hook_cb(Box::new(callback));
+
+            let definition = ul_sys::JSClassDefinition {
+                className: class_name.as_ptr(),
+                finalize: Some(finalize),
+                callAsFunction: Some(call),
+                ..mem::zeroed()
+            };
+
+            // objects retain their class, so it is released again when
+            // dropped at the end of the scope
+            let class = JSClass {
+                raw: ul_sys::JSClassCreate(&definition),
+            };
+
+            let raw = ul_sys::JSObjectMake(ctx.raw, class.raw, data);
+
+            Ok(JSObject {
+                raw,
+                value: JSValue {
+                    raw,
+                    ctx: ctx.raw,
+                },
+            })
+        }
+    }
+
     /// Gets an iterator over the names of an object's enumerable properties.
     ///
     /// ```
@@ -60,7 +174,7 @@ impl JSObject {
     ///   the property's name.
     ///
     /// Returns the property's value if object has the property, otherwise
-    /// the undefined value.
+    /// the undefined value, or the exception thrown by a getter.
     ///
     /// ```
     /// # use javascriptcore::{JSContext, JSObject, JSString, JSValue};
@@ -68,24 +182,25 @@ impl JSObject {
     /// let v = JSValue::new_from_json(&ctx, "{\"id\": 123}").expect("valid object");
     /// let o = v.as_object().expect("object");
     ///
-    /// let n = o.get_property("id");
+    /// let n = o.get_property("id").expect("value");
     /// assert!(n.is_number());
     /// // Remember that this will be an f64 now!
     /// assert_eq!(n.as_number().expect("number"), 123.0);
     /// ```
     ///
     /// [`JSString`]: struct.JSString.html
-    pub fn get_property<S>(&self, name: S) -> JSValue
+    pub fn get_property<S>(&self, name: S) -> Result<JSValue, JSException>
         where
             S: Into<JSString>,
     {
         let mut e: ul_sys::JSValueRef = ptr::null_mut();
         let v =
             unsafe { ul_sys::JSObjectGetProperty(self.value.ctx, self.raw, name.into().raw, &mut e) };
-        JSValue {
+        self.check_exception(e)?;
+        Ok(JSValue {
             raw: v,
             ctx: self.value.ctx,
-        }
+        })
     }
 
     /// Gets a property from an object by numeric index.
@@ -93,7 +208,7 @@ impl JSObject {
     /// * `index`: An integer value that is the property's name.
     ///
     /// Returns the property's value if object has the property,
-    /// otherwise the undefined value.
+    /// otherwise the undefined value, or the exception thrown by a getter.
     ///
     /// Calling `get_property_at_index` is equivalent to calling
     /// `get_property` with a string containing `index`,
@@ -106,9 +221,9 @@ impl JSObject {
     /// let v = JSValue::new_from_json(&ctx, "[3, true, \"abc\"]").expect("valid array");
     /// let o = v.as_object().expect("object");
     ///
-    /// let n = o.get_property_at_index(0).as_number().expect("number");
-    /// let b = o.get_property_at_index(1).as_boolean();
-    /// let s = o.get_property_at_index(2).as_string().expect("string");
+    /// let n = o.get_property_at_index(0).expect("value").as_number().expect("number");
+    /// let b = o.get_property_at_index(1).expect("value").as_boolean();
+    /// let s = o.get_property_at_index(2).expect("value").as_string().expect("string");
     ///
     /// assert_eq!(n, 3.0);
     /// assert_eq!(b, true);
@@ -124,17 +239,361 @@ impl JSObject {
     /// let o = v.as_object().expect("object");
     ///
     /// // There is no property "0", so this will be `undefined`:
-    /// assert!(o.get_property_at_index(0).is_undefined());
-    /// assert_eq!(o.get_property_at_index(1).as_boolean(), true);
-    /// assert_eq!(o.get_property_at_index(2).as_string().expect("string"), "abc");
+    /// assert!(o.get_property_at_index(0).expect("value").is_undefined());
+    /// assert_eq!(o.get_property_at_index(1).expect("value").as_boolean(), true);
+    /// assert_eq!(o.get_property_at_index(2).expect("value").as_string().expect("string"), "abc");
     /// ```
-    pub fn get_property_at_index(&self, index: u32) -> JSValue {
+    pub fn get_property_at_index(&self, index: u32) -> Result<JSValue, JSException> {
         let mut e: ul_sys::JSValueRef = ptr::null_mut();
         let v = unsafe { ul_sys::JSObjectGetPropertyAtIndex(self.value.ctx, self.raw, index, &mut e) };
+        self.check_exception(e)?;
+        Ok(JSValue {
+            raw: v,
+            ctx: self.value.ctx,
+        })
+    }
+
+    /// Sets a property on an object.
+    ///
+    /// * `name`: A value that can be converted to a [`JSString`] containing
+    ///   the property's name.
+    /// * `value`: The value to assign to the property.
+    ///
+    /// Returns the exception thrown by a setter or when assigning to a
+    /// read-only property in strict mode, if any.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSValue};
+    /// let ctx = JSContext::default();
+    /// let o = JSObject::new(&ctx);
+    ///
+    /// o.set_property("id", &JSValue::new_number(&ctx, 123.0)).expect("set");
+    /// assert_eq!(o.get_property("id").expect("value").as_number().expect("number"), 123.0);
+    /// ```
+    ///
+    /// [`JSString`]: struct.JSString.html
+    pub fn set_property<S>(&self, name: S, value: &JSValue) -> Result<(), JSException>
+        where
+            S: Into<JSString>,
+    {
+        self.set_property_with_attributes(name, value, JSPropertyAttributes::NONE)
+    }
+
+    /// Sets a property on an object with the given attributes.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSPropertyAttributes, JSValue};
+    /// let ctx = JSContext::default();
+    /// let o = JSObject::new(&ctx);
+    ///
+    /// let attributes = JSPropertyAttributes::READ_ONLY | JSPropertyAttributes::DONT_ENUM;
+    /// o.set_property_with_attributes("id", &JSValue::new_number(&ctx, 123.0), attributes)
+    ///     .expect("set");
+    /// assert_eq!(o.property_names().count(), 0);
+    /// ```
+    pub fn set_property_with_attributes<S>(
+        &self,
+        name: S,
+        value: &JSValue,
+        attributes: JSPropertyAttributes,
+    ) -> Result<(), JSException>
+        where
+            S: Into<JSString>,
+    {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        unsafe {
+            ul_sys::JSObjectSetProperty(
+                self.value.ctx,
+                self.raw,
+                name.into().raw,
+                value.raw,
+                attributes.bits(),
+                &mut e,
+            )
+        };
+        self.check_exception(e)
+    }
+
+    /// Sets a property on an object by numeric index.
+    ///
+    /// Calling `set_property_at_index` is equivalent to calling
+    /// `set_property` with a string containing `index`, but provides
+    /// optimized access to numeric properties.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSValue};
+    /// let ctx = JSContext::default();
+    /// let a = JSObject::new_array(&ctx, &[]).expect("array");
+    ///
+    /// a.set_property_at_index(1, &JSValue::new_boolean(&ctx, true)).expect("set");
+    /// assert_eq!(a.get_property("length").expect("value").as_number().expect("number"), 2.0);
+    /// ```
+    pub fn set_property_at_index(&self, index: u32, value: &JSValue) -> Result<(), JSException> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        unsafe {
+            ul_sys::JSObjectSetPropertyAtIndex(self.value.ctx, self.raw, index, value.raw, &mut e)
+        };
+        self.check_exception(e)
+    }
+
+    /// Deletes a property from an object.
+    ///
+    /// Returns `true` if the property was deleted, `false` if it is marked
+    /// `DONT_DELETE`, or the exception that was thrown.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSString, JSValue};
+    /// let ctx = JSContext::default();
+    /// let v = JSValue::new_from_json(&ctx, "{\"id\": 123}").expect("valid object");
+    /// let o = v.as_object().expect("object");
+    ///
+    /// assert!(o.delete_property("id").expect("delete"));
+    /// assert!(!o.has_property("id"));
+    /// ```
+    pub fn delete_property<S>(&self, name: S) -> Result<bool, JSException>
+        where
+            S: Into<JSString>,
+    {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let deleted =
+            unsafe { ul_sys::JSObjectDeleteProperty(self.value.ctx, self.raw, name.into().raw, &mut e) };
+        self.check_exception(e)?;
+        Ok(deleted)
+    }
+
+    /// Gets an object's prototype.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject};
+    /// let ctx = JSContext::default();
+    /// let o = JSObject::new(&ctx);
+    ///
+    /// assert!(o.get_prototype().is_object());
+    /// ```
+    pub fn get_prototype(&self) -> JSValue {
         JSValue {
+            raw: unsafe { ul_sys::JSObjectGetPrototype(self.value.ctx, self.raw) },
+            ctx: self.value.ctx,
+        }
+    }
+
+    /// Sets an object's prototype.
+    ///
+    /// * `prototype`: The object to use as the prototype, or `null`.
+    ///
+    /// ```
+    /// # use javascriptcore::{JSContext, JSObject, JSValue};
+    /// let ctx = JSContext::default();
+    /// let proto = JSObject::new(&ctx);
+    /// proto.set_property("id", &JSValue::new_number(&ctx, 123.0)).expect("set");
+    ///
+    /// let o = JSObject::new(&ctx);
+    /// o.set_prototype(&proto);
+    /// assert!(o.has_property("id"));
+    /// ```
+    pub fn set_prototype(&self, prototype: &JSValue) {
+        unsafe { ul_sys::JSObjectSetPrototype(self.value.ctx, self.raw, prototype.raw) }
+    }
+
+    /// Tests whether an object can be called as a function.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let v = evaluate_script(&ctx, "(function() {})", None, "test.js", 1).expect("value");
+    ///
+    /// assert!(v.as_object().expect("object").is_function());
+    /// assert!(!JSObject::new(&ctx).is_function());
+    /// ```
+    pub fn is_function(&self) -> bool {
+        unsafe { ul_sys::JSObjectIsFunction(self.value.ctx, self.raw) }
+    }
+
+    /// Calls an object as a function.
+    ///
+    /// * `this`: The object to use as `this`, or `None` to use the global
+    ///   object.
+    /// * `args`: The arguments to pass to the function.
+    ///
+    /// Returns either the function's return value, or the exception that
+    /// was thrown. Calling an object that isn't a function throws a
+    /// `TypeError`.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let v = evaluate_script(&ctx, "(function(a, b) { return a + b; })", None, "test.js", 1)
+    ///     .expect("value");
+    /// let f = v.as_object().expect("object");
+    ///
+    /// let args = [JSValue::new_number(&ctx, 1.0), JSValue::new_number(&ctx, 2.0)];
+    /// let sum = f.call(None, &args).expect("call");
+    /// assert_eq!(sum.as_number().expect("number"), 3.0);
+    /// ```
+    pub fn call(&self, this: Option<&JSObject>, args: &[JSValue]) -> Result<JSValue, JSException> {
+        if !self.is_function() {
+            return Err(self.type_error("object is not a function"));
+        }
+
+        let args: Vec<ul_sys::JSValueRef> = args.iter().map(|arg| arg.raw).collect();
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let v = unsafe {
+            ul_sys::JSObjectCallAsFunction(
+                self.value.ctx,
+                self.raw,
+                this.map(|t| t.raw).unwrap_or_else(ptr::null_mut),
+                args.len() as _,
+                args.as_ptr(),
+                &mut e,
+            )
+        };
+        self.check_exception(e)?;
+        Ok(JSValue {
             raw: v,
             ctx: self.value.ctx,
+        })
+    }
+
+    /// Tests whether an object can be called as a constructor.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let v = evaluate_script(&ctx, "Date", None, "test.js", 1).expect("value");
+    ///
+    /// assert!(v.as_object().expect("object").is_constructor());
+    /// ```
+    pub fn is_constructor(&self) -> bool {
+        unsafe { ul_sys::JSObjectIsConstructor(self.value.ctx, self.raw) }
+    }
+
+    /// Calls an object as a constructor, like `new` would.
+    ///
+    /// * `args`: The arguments to pass to the constructor.
+    ///
+    /// Returns either the constructed object, or the exception that was
+    /// thrown. Calling an object that isn't a constructor throws a
+    /// `TypeError`.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let v = evaluate_script(&ctx, "Array", None, "test.js", 1).expect("value");
+    /// let array = v.as_object().expect("object");
+    ///
+    /// let a = array.construct(&[JSValue::new_number(&ctx, 3.0)]).expect("construct");
+    /// assert!(a.is_array());
+    /// assert_eq!(a.get_property("length").expect("value").as_number().expect("number"), 3.0);
+    /// ```
+    pub fn construct(&self, args: &[JSValue]) -> Result<JSObject, JSException> {
+        if !self.is_constructor() {
+            return Err(self.type_error("object is not a constructor"));
         }
+
+        let args: Vec<ul_sys::JSValueRef> = args.iter().map(|arg| arg.raw).collect();
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let o = unsafe {
+            ul_sys::JSObjectCallAsConstructor(
+                self.value.ctx,
+                self.raw,
+                args.len() as _,
+                args.as_ptr(),
+                &mut e,
+            )
+        };
+        self.check_exception(e)?;
+        Ok(JSObject {
+            raw: o,
+            value: JSValue {
+                raw: o,
+                ctx: self.value.ctx,
+            },
+        })
+    }
+
+    pub(super) fn type_error(&self, message: &str) -> JSException {
+        let ctx = self.value.ctx;
+        let message = JSValue {
+            raw: unsafe { ul_sys::JSValueMakeString(ctx, JSString::from(message).raw) },
+            ctx,
+        };
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let global = unsafe { ul_sys::JSContextGetGlobalObject(ctx) };
+        let constructor = unsafe {
+            ul_sys::JSObjectGetProperty(ctx, global, JSString::from("TypeError").raw, &mut e)
+        };
+        let error = unsafe {
+            ul_sys::JSObjectCallAsConstructor(
+                ctx,
+                constructor as ul_sys::JSObjectRef,
+                1,
+                &message.raw,
+                &mut e,
+            )
+        };
+
+        JSException {
+            value: JSValue {
+                raw: if error.is_null() { e } else { error },
+                ctx,
+            },
+        }
+    }
+
+    pub(super) fn check_exception(&self, e: ul_sys::JSValueRef) -> Result<(), JSException> {
+        if e.is_null() {
+            Ok(())
+        } else {
+            Err(JSException {
+                value: JSValue {
+                    raw: e,
+                    ctx: self.value.ctx,
+                },
+            })
+        }
+    }
+}
+
+/// Attributes of a property set through
+/// `JSObject::set_property_with_attributes`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct JSPropertyAttributes(u32);
+
+impl JSPropertyAttributes {
+    pub const NONE: JSPropertyAttributes = JSPropertyAttributes(ul_sys::kJSPropertyAttributeNone);
+    /// The property can't be assigned to.
+    pub const READ_ONLY: JSPropertyAttributes =
+        JSPropertyAttributes(ul_sys::kJSPropertyAttributeReadOnly);
+    /// The property isn't enumerated by `property_names` and `for...in`.
+    pub const DONT_ENUM: JSPropertyAttributes =
+        JSPropertyAttributes(ul_sys::kJSPropertyAttributeDontEnum);
+    /// The property can't be deleted.
+    pub const DONT_DELETE: JSPropertyAttributes =
+        JSPropertyAttributes(ul_sys::kJSPropertyAttributeDontDelete);
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(self, other: JSPropertyAttributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for JSPropertyAttributes {
+    type Output = JSPropertyAttributes;
+
+    fn bitor(self, other: JSPropertyAttributes) -> JSPropertyAttributes {
+        JSPropertyAttributes(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for JSPropertyAttributes {
+    fn bitor_assign(&mut self, other: JSPropertyAttributes) {
+        self.0 |= other.0;
     }
 }
 
@@ -183,7 +642,11 @@ impl Iterator for JSObjectPropertyNameIter {
 
 #[cfg(test)]
 mod tests {
-    use super::super::{JSContext, JSValue};
+    use super::super::{
+        evaluate_script, garbage_collect, JSContext, JSObject, JSPropertyAttributes, JSValue,
+    };
+    use crate::Error;
+    use std::rc::Rc;
 
     #[test]
     fn can_has_property() {
@@ -199,8 +662,8 @@ mod tests {
         let ctx = JSContext::default();
         let v = JSValue::new_from_json(&ctx, "{\"id\": 123}").expect("value");
         let o = v.as_object().expect("object");
-        assert!(o.get_property("id").is_number());
-        assert!(o.get_property("no-such-value").is_undefined());
+        assert!(o.get_property("id").expect("value").is_number());
+        assert!(o.get_property("no-such-value").expect("value").is_undefined());
     }
 
     #[test]
@@ -208,10 +671,10 @@ mod tests {
         let ctx = JSContext::default();
         let v = JSValue::new_from_json(&ctx, "[3, true, \"abc\"]").expect("value");
         let o = v.as_object().expect("object");
-        assert!(o.get_property_at_index(0).is_number());
-        assert!(o.get_property_at_index(1).is_boolean());
-        assert!(o.get_property_at_index(2).is_string());
-        assert!(o.get_property_at_index(5).is_undefined());
+        assert!(o.get_property_at_index(0).expect("value").is_number());
+        assert!(o.get_property_at_index(1).expect("value").is_boolean());
+        assert!(o.get_property_at_index(2).expect("value").is_string());
+        assert!(o.get_property_at_index(5).expect("value").is_undefined());
     }
 
     #[test]
@@ -232,4 +695,170 @@ mod tests {
         assert!(v.is_object());
         assert!(o.is_object());
     }
+
+    #[test]
+    fn can_call_function() {
+        let ctx = JSContext::default();
+        let f = JSObject::new_function(&ctx, "answer", |ctx, _, _, _, _, _| unsafe {
+            ul_sys::JSValueMakeNumber(ctx, 42.0)
+        })
+        .expect("function");
+
+        let v = unsafe {
+            ul_sys::JSObjectCallAsFunction(
+                ctx.raw,
+                f.raw,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+            )
+        };
+        let v = JSValue { raw: v, ctx: ctx.raw };
+        assert_eq!(v.as_number().expect("number"), 42.0);
+    }
+
+    #[test]
+    fn functions_are_finalized() {
+        let alive = Rc::new(());
+
+        let ctx = JSContext::default();
+        for _ in 0..5000 {
+            let alive = alive.clone();
+            JSObject::new_function(&ctx, "f", move |ctx, _, _, _, _, _| unsafe {
+                let _ = &alive;
+                ul_sys::JSValueMakeUndefined(ctx)
+            })
+            .expect("function");
+        }
+        assert_eq!(Rc::strong_count(&alive), 5001);
+
+        // garbage_collect only hints at a collection and finalizers run
+        // when their memory is swept, so keep allocating functions until
+        // the unreachable ones have been reclaimed
+        for _ in 0..100 {
+            if Rc::strong_count(&alive) < 100 {
+                break;
+            }
+
+            garbage_collect(&ctx);
+            for _ in 0..1000 {
+                JSObject::new_function(&ctx, "g", |ctx, _, _, _, _, _| unsafe {
+                    ul_sys::JSValueMakeUndefined(ctx)
+                })
+                .expect("function");
+            }
+        }
+
+        // a few may still be referenced from the machine stack, which is
+        // scanned conservatively
+        assert!(Rc::strong_count(&alive) < 100);
+
+        drop(ctx);
+        assert_eq!(Rc::strong_count(&alive), 1);
+    }
+
+    #[test]
+    fn new_function_rejects_nul_in_name() {
+        let ctx = JSContext::default();
+        let f = JSObject::new_function(&ctx, "a\0b", |ctx, _, _, _, _, _| unsafe {
+            ul_sys::JSValueMakeUndefined(ctx)
+        });
+        assert!(matches!(f, Err(Error::InteriorNul(_))));
+    }
+
+    #[test]
+    fn can_set_and_delete_property() {
+        let ctx = JSContext::default();
+        let o = JSObject::new(&ctx);
+        o.set_property("id", &JSValue::new_number(&ctx, 123.0)).expect("set");
+        assert!(o.has_property("id"));
+        assert!(o.delete_property("id").expect("delete"));
+        assert!(!o.has_property("id"));
+
+        o.set_property_with_attributes(
+            "fixed",
+            &JSValue::new_boolean(&ctx, true),
+            JSPropertyAttributes::READ_ONLY
+                | JSPropertyAttributes::DONT_ENUM
+                | JSPropertyAttributes::DONT_DELETE,
+        )
+        .expect("set");
+        o.set_property("fixed", &JSValue::new_boolean(&ctx, false)).expect("set");
+        assert!(o.get_property("fixed").expect("value").as_boolean());
+        assert_eq!(o.property_names().count(), 0);
+        assert!(!o.delete_property("fixed").expect("delete"));
+    }
+
+    #[test]
+    fn can_make_array() {
+        let ctx = JSContext::default();
+        let a = JSObject::new_array(&ctx, &[JSValue::new_number(&ctx, 3.0)]).expect("array");
+        assert!(a.is_array());
+        a.set_property_at_index(1, &JSValue::new_string(&ctx, "abc")).expect("set");
+        assert!(a.get_property_at_index(1).expect("value").is_string());
+        assert_eq!(a.get_property("length").expect("value").as_number().expect("number"), 2.0);
+    }
+
+    #[test]
+    fn can_set_prototype() {
+        let ctx = JSContext::default();
+        let proto = JSObject::new(&ctx);
+        proto.set_property("id", &JSValue::new_number(&ctx, 123.0)).expect("set");
+
+        let o = JSObject::new(&ctx);
+        o.set_prototype(&proto);
+        assert!(o.get_prototype() == *proto);
+        assert!(o.get_property("id").expect("value").is_number());
+    }
+
+    #[test]
+    fn property_exceptions_are_returned() {
+        let ctx = JSContext::default();
+        let v = evaluate_script(
+            &ctx,
+            "({ get bad() { throw new Error('get') }, set bad(v) { throw new Error('set') } })",
+            None,
+            "test.js",
+            1,
+        )
+        .expect("value");
+        let o = v.as_object().expect("object");
+        assert_eq!(o.get_property("bad").unwrap_err().message(), "get");
+        assert_eq!(
+            o.set_property("bad", &JSValue::new_null(&ctx)).unwrap_err().message(),
+            "set"
+        );
+    }
+
+    #[test]
+    fn can_call_and_construct() {
+        let ctx = JSContext::default();
+        let v = evaluate_script(&ctx, "(function(a) { return this.x + a; })", None, "test.js", 1)
+            .expect("value");
+        let f = v.as_object().expect("object");
+        assert!(f.is_function());
+
+        let this = JSObject::new(&ctx);
+        this.set_property("x", &JSValue::new_number(&ctx, 1.0)).expect("set");
+        let r = f.call(Some(&this), &[JSValue::new_number(&ctx, 2.0)]).expect("call");
+        assert_eq!(r.as_number().expect("number"), 3.0);
+
+        let v = evaluate_script(&ctx, "(function(a) { this.a = a; })", None, "test.js", 1)
+            .expect("value");
+        let c = v.as_object().expect("object");
+        assert!(c.is_constructor());
+        let o = c.construct(&[JSValue::new_string(&ctx, "abc")]).expect("construct");
+        assert_eq!(o.get_property("a").expect("value").as_string().expect("string"), "abc");
+
+        let o = JSObject::new(&ctx);
+        assert!(!o.is_function());
+        assert!(o.call(None, &[]).is_err());
+        assert!(o.construct(&[]).is_err());
+
+        let v = evaluate_script(&ctx, "(function() { throw new Error('abc'); })", None, "test.js", 1)
+            .expect("value");
+        let f = v.as_object().expect("object");
+        assert_eq!(f.call(None, &[]).unwrap_err().message(), "abc");
+    }
 }
@@ -0,0 +1,521 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{IntoJSResult, IntoJSValue, JSContext, JSException, JSObject, JSString, JSValue};
+use std::cell::{Cell, RefCell};
+use std::sync::mpsc;
+use std::{mem, ptr};
+
+thread_local! {
+    // settlements sent through `PromiseSender`s, polled by
+    // `settle_pending_promises` until they return `true`
+    static PENDING: RefCell<Vec<Pending>> = RefCell::new(Vec::new());
+
+    // identifies the entries of `PENDING` across settling them
+    static NEXT_PENDING_ID: Cell<u64> = Cell::new(0);
+}
+
+struct Pending {
+    id: u64,
+    // the global context of the promise, retained by the settlement
+    ctx: ul_sys::JSGlobalContextRef,
+    settle: Box<dyn FnMut() -> bool>,
+}
+
+/// The resolve and reject functions of a promise created through
+/// `JSValue::new_promise`.
+///
+/// The promise stays pending forever if the resolver is dropped without
+/// settling it.
+pub struct Resolver {
+    ctx: JSContext,
+    resolve: ul_sys::JSObjectRef,
+    reject: ul_sys::JSObjectRef,
+}
+
+/// Settles a promise from any thread, see `Resolver::into_sender` and
+/// `promise_channel`.
+///
+/// Dropping the sender without settling the promise rejects it.
+pub struct PromiseSender<T> {
+    sender: mpsc::Sender<Result<T, String>>,
+}
+
+/// A promise settled through a `PromiseSender`, created in JavaScript once
+/// converted with `IntoJSResult`, eg. when returned from a function bound
+/// through `JSFunction`.
+pub struct PendingPromise<T> {
+    receiver: mpsc::Receiver<Result<T, String>>,
+}
+
+impl JSValue {
+    /// Creates a pending promise, along with the `Resolver` settling it.
+    ///
+    /// Like the other constructors of the engine, `JSObjectMakeDeferredPromise`
+    /// can throw (eg. once the VM is terminating), so the exception is
+    /// returned rather than a promise that doesn't exist.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let (promise, resolver) = JSValue::new_promise(&ctx).expect("promise");
+    ///
+    /// resolver.resolve(&JSValue::new_number(&ctx, 42.0)).expect("resolve");
+    /// ```
+    pub fn new_promise(ctx: &JSContext) -> Result<(JSObject, Resolver), JSException> {
+        let mut resolve: ul_sys::JSObjectRef = ptr::null_mut();
+        let mut reject: ul_sys::JSObjectRef = ptr::null_mut();
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+
+        let promise = unsafe {
+            ul_sys::JSObjectMakeDeferredPromise(ctx.raw, &mut resolve, &mut reject, &mut e)
+        };
+        if promise.is_null() {
+            return Err(JSException {
+                value: JSValue {
+                    raw: e,
+                    ctx: ctx.raw,
+                },
+            });
+        }
+
+        // only referenced from Rust until the promise is settled
+        unsafe {
+            ul_sys::JSValueProtect(ctx.raw, resolve);
+            ul_sys::JSValueProtect(ctx.raw, reject);
+        }
+
+        let resolver = Resolver {
+            ctx: unsafe { JSContext::from_context_ref(ctx.raw) },
+            resolve,
+            reject,
+        };
+
+        Ok((
+            JSObject {
+                raw: promise,
+                value: JSValue {
+                    raw: promise,
+                    ctx: ctx.raw,
+                },
+            },
+            resolver,
+        ))
+    }
+}
+
+impl Resolver {
+    /// Fulfills the promise with `value`.
+    pub fn resolve(self, value: &JSValue) -> Result<(), JSException> {
+        self.settle(self.resolve, value)
+    }
+
+    /// Rejects the promise with `reason`.
+    pub fn reject(self, reason: &JSValue) -> Result<(), JSException> {
+        self.settle(self.reject, reason)
+    }
+
+    /// Rejects the promise with an `Error` with the given message.
+    pub fn reject_with_message<S: Into<JSString>>(self, message: S) -> Result<(), JSException> {
+        let ctx = self.ctx.raw;
+        let message = JSValue::new_string(&self.ctx, message);
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let error = unsafe { ul_sys::JSObjectMakeError(ctx, 1, &message.raw, &mut e) };
+        if error.is_null() {
+            return Err(JSException {
+                value: JSValue { raw: e, ctx },
+            });
+        }
+
+        self.settle(self.reject, &JSValue { raw: error, ctx })
+    }
+
+    /// Turns the resolver into a `PromiseSender`, which can be moved to
+    /// another thread.
+    ///
+    /// Values sent through it are converted and settle the promise on the
+    /// thread of the resolver during the next `settle_pending_promises`,
+    /// which `Renderer::update` calls.
+    pub fn into_sender<T: IntoJSValue + 'static>(self) -> PromiseSender<T> {
+        let (sender, receiver) = mpsc::channel();
+
+        settle_later(self, receiver);
+
+        PromiseSender { sender }
+    }
+
+    fn settle(&self, function: ul_sys::JSObjectRef, value: &JSValue) -> Result<(), JSException> {
+        let ctx = self.ctx.raw;
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        unsafe {
+            ul_sys::JSObjectCallAsFunction(ctx, function, ptr::null_mut(), 1, &value.raw, &mut e)
+        };
+        if e.is_null() {
+            Ok(())
+        } else {
+            Err(JSException {
+                value: JSValue { raw: e, ctx },
+            })
+        }
+    }
+}
+
+impl Drop for Resolver {
+    fn drop(&mut self) {
+        unsafe {
+            ul_sys::JSValueUnprotect(self.ctx.raw, self.resolve);
+            ul_sys::JSValueUnprotect(self.ctx.raw, self.reject);
+        }
+    }
+}
+
+impl<T> PromiseSender<T> {
+    /// Fulfills the promise with `value`.
+    pub fn resolve(self, value: T) {
+        // the promise is gone if the receiving side has been dropped
+        let _ = self.sender.send(Ok(value));
+    }
+
+    /// Rejects the promise with an `Error` with the given message.
+    pub fn reject<S: Into<String>>(self, message: S) {
+        let _ = self.sender.send(Err(message.into()));
+    }
+}
+
+// Failing to create the promise is thrown to the caller, and the
+// `PromiseSender` settles nothing once the receiver is dropped.
+impl<T: IntoJSValue + 'static> IntoJSResult for PendingPromise<T> {
+    fn into_js_result(self, ctx: &JSContext) -> Result<JSValue, String> {
+        let (promise, resolver) = JSValue::new_promise(ctx).map_err(|e| e.to_string())?;
+
+        settle_later(resolver, self.receiver);
+
+        Ok(promise.value)
+    }
+}
+
+/// Creates a promise to be settled from any thread.
+///
+/// The `PendingPromise` becomes a JavaScript promise once converted with
+/// `IntoJSResult`, so it can be returned from a bound function and settled
+/// later on by a background task:
+///
+/// ```ignore
+/// view.bind_function("load", |path: String| {
+///     let (sender, promise) = ul::jsc::promise_channel();
+///
+///     std::thread::spawn(move || match std::fs::read_to_string(&path) {
+///         Ok(contents) => sender.resolve(contents),
+///         Err(e) => sender.reject(e.to_string()),
+///     });
+///
+///     promise
+/// })?;
+/// ```
+pub fn promise_channel<T>() -> (PromiseSender<T>, PendingPromise<T>) {
+    let (sender, receiver) = mpsc::channel();
+
+    (PromiseSender { sender }, PendingPromise { receiver })
+}
+
+/// Settles the promises whose `PromiseSender` sent a value since the last
+/// call, on the current thread.
+///
+/// Called by `Renderer::update`, only needs to be called explicitly when
+/// using a `JSContext` of your own.
+pub fn settle_pending_promises() {
+    // promises registered while settling wait for the next call
+    let ids: Vec<u64> = PENDING.with(|pending| pending.borrow().iter().map(|p| p.id).collect());
+
+    for id in ids {
+        // Settling runs promise reactions, which may destroy views and
+        // discard their promises, so the others stay in `PENDING` until
+        // their turn. Those discarded in the meantime are gone.
+        let taken = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let index = pending.iter().position(|p| p.id == id)?;
+
+            Some(pending.remove(index))
+        });
+
+        if let Some(mut taken) = taken {
+            // nothing ran if it isn't settled yet
+            if !(taken.settle)() {
+                PENDING.with(|pending| pending.borrow_mut().push(taken));
+            }
+        }
+    }
+}
+
+/// Drops the settlements of the pending promises of `ctx`, which stay
+/// pending forever.
+///
+/// Called when a view is destroyed, only needs to be called explicitly
+/// before releasing a `JSContext` of your own for good.
+pub fn discard_pending_promises(ctx: &JSContext) {
+    discard_pending_promises_where(|pending| pending == ctx.raw);
+}
+
+// Drops the settlements of all pending promises on this thread, before the
+// renderer they belong to is destroyed.
+pub(crate) fn discard_all_pending_promises() {
+    discard_pending_promises_where(|_| true);
+}
+
+fn discard_pending_promises_where<F>(mut discard: F)
+    where
+        F: FnMut(ul_sys::JSGlobalContextRef) -> bool,
+{
+    // dropped outside of the borrow, releasing a context may run finalizers
+    let discarded: Vec<Pending> = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let (discarded, kept): (Vec<_>, Vec<_>) = mem::take(&mut *pending)
+            .into_iter()
+            .partition(|p| discard(p.ctx));
+        *pending = kept;
+
+        discarded
+    });
+
+    drop(discarded);
+}
+
+fn settle_later<T: IntoJSValue + 'static>(
+    resolver: Resolver,
+    receiver: mpsc::Receiver<Result<T, String>>,
+) {
+    let ctx = resolver.ctx.raw;
+    let mut resolver = Some(resolver);
+
+    let settle = move || {
+        let settlement = match receiver.try_recv() {
+            Ok(settlement) => settlement,
+            Err(mpsc::TryRecvError::Empty) => return false,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err("promise sender dropped without settling".to_owned())
+            }
+        };
+
+        if let Some(resolver) = resolver.take() {
+            // exceptions can only come from a broken context at this point
+            let _ = match settlement {
+                Ok(value) => {
+                    let value = value.into_js_value(&resolver.ctx);
+                    resolver.resolve(&value)
+                }
+                Err(message) => resolver.reject_with_message(message),
+            };
+        }
+
+        true
+    };
+
+    PENDING.with(|pending| {
+        pending.borrow_mut().push(Pending {
+            id: NEXT_PENDING_ID.with(|id| id.replace(id.get() + 1)),
+            ctx,
+            settle: Box::new(settle),
+        })
+    });
+}
+
+impl JSObject {
+    /// Calls `on_fulfilled` with the value of a promise once it is
+    /// fulfilled, like `promise.then(on_fulfilled)`.
+    ///
+    /// Returns the promise returned by `then`.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let (promise, resolver) = JSValue::new_promise(&ctx).expect("promise");
+    ///
+    /// promise.then(|value| println!("{:?}", value.as_number())).expect("then");
+    /// resolver.resolve(&JSValue::new_number(&ctx, 42.0)).expect("resolve");
+    /// ```
+    pub fn then<F>(&self, on_fulfilled: F) -> Result<JSObject, JSException>
+        where
+            F: FnOnce(JSValue) + 'static,
+    {
+        self.add_reaction("then", on_fulfilled)
+    }
+
+    /// Calls `on_rejected` with the reason of a promise once it is
+    /// rejected, like `promise.catch(on_rejected)`.
+    ///
+    /// Returns the promise returned by `catch`.
+    pub fn catch<F>(&self, on_rejected: F) -> Result<JSObject, JSException>
+        where
+            F: FnOnce(JSValue) + 'static,
+    {
+        self.add_reaction("catch", on_rejected)
+    }
+
+    fn add_reaction<F>(&self, method: &str, callback: F) -> Result<JSObject, JSException>
+        where
+            F: FnOnce(JSValue) + 'static,
+    {
+        let ctx = unsafe { JSContext::from_context_ref(self.value.ctx) };
+        let mut callback = Some(callback);
+
+        let function = JSObject::new_function(&ctx, method, move |ctx, _, _, argc, argv, _| unsafe {
+            if let Some(callback) = callback.take() {
+                let value = if argc > 0 {
+                    *argv
+                } else {
+                    ul_sys::JSValueMakeUndefined(ctx)
+                };
+
+                callback(JSValue { raw: value, ctx });
+            }
+
+            ul_sys::JSValueMakeUndefined(ctx)
+        })
+        .expect("method names contain no NUL bytes");
+
+        self.call_method(method, &[function.value])?.as_object()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{evaluate_script, JSContext, JSValue};
+    use super::{
+        discard_pending_promises, discard_pending_promises_where, promise_channel,
+        settle_pending_promises, IntoJSResult, Pending, PENDING,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn resolve_and_reject() {
+        let ctx = JSContext::default();
+        let settled = Rc::new(RefCell::new(Vec::new()));
+
+        let (promise, resolver) = JSValue::new_promise(&ctx).unwrap();
+        let s = settled.clone();
+        promise.then(move |v| s.borrow_mut().push(v.as_number().unwrap())).unwrap();
+        resolver.resolve(&JSValue::new_number(&ctx, 1.0)).unwrap();
+
+        let (promise, resolver) = JSValue::new_promise(&ctx).unwrap();
+        let s = settled.clone();
+        promise.catch(move |_| s.borrow_mut().push(2.0)).unwrap();
+        resolver.reject_with_message("no").unwrap();
+
+        evaluate_script(&ctx, "0", None, "test.js", 1).unwrap();
+        assert_eq!(*settled.borrow(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn observe_js_promise() {
+        let ctx = JSContext::default();
+        let message = Rc::new(RefCell::new(None));
+
+        let v = evaluate_script(&ctx, "Promise.reject(new Error('abc'))", None, "test.js", 1)
+            .unwrap();
+        let m = message.clone();
+        v.as_object()
+            .unwrap()
+            .catch(move |e| {
+                let e = e.as_object().unwrap();
+                let message = e.get_property("message").unwrap().as_string().unwrap();
+                *m.borrow_mut() = Some(message.to_string());
+            })
+            .unwrap();
+
+        evaluate_script(&ctx, "0", None, "test.js", 1).unwrap();
+        assert_eq!(message.borrow().as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn settle_from_thread() {
+        let ctx = JSContext::default();
+        let result = Rc::new(RefCell::new(None));
+
+        let (sender, pending) = promise_channel::<String>();
+        let promise = pending.into_js_result(&ctx).unwrap().as_object().unwrap();
+        let r = result.clone();
+        promise
+            .then(move |v| *r.borrow_mut() = Some(v.as_string().unwrap().to_string()))
+            .unwrap();
+
+        thread::spawn(move || sender.resolve("done".to_owned())).join().unwrap();
+
+        settle_pending_promises();
+        evaluate_script(&ctx, "0", None, "test.js", 1).unwrap();
+        assert_eq!(result.borrow().as_deref(), Some("done"));
+
+        let (sender, pending) = promise_channel::<f64>();
+        let promise = pending.into_js_result(&ctx).unwrap().as_object().unwrap();
+        let r = result.clone();
+        promise.catch(move |_| *r.borrow_mut() = None).unwrap();
+        drop(sender);
+
+        settle_pending_promises();
+        evaluate_script(&ctx, "0", None, "test.js", 1).unwrap();
+        assert_eq!(*result.borrow(), None);
+    }
+
+    #[test]
+    fn discard_pending() {
+        let ctx = JSContext::default();
+        let other = JSContext::default();
+
+        let (sender, pending) = promise_channel::<f64>();
+        pending.into_js_result(&ctx).unwrap();
+        let (_other_sender, other_pending) = promise_channel::<f64>();
+        other_pending.into_js_result(&other).unwrap();
+        assert_eq!(PENDING.with(|pending| pending.borrow().len()), 2);
+
+        discard_pending_promises(&ctx);
+        assert_eq!(PENDING.with(|pending| pending.borrow().len()), 1);
+
+        // nothing left to settle for the discarded promise
+        sender.resolve(1.0);
+        settle_pending_promises();
+        assert_eq!(PENDING.with(|pending| pending.borrow().len()), 1);
+
+        discard_pending_promises(&other);
+        assert_eq!(PENDING.with(|pending| pending.borrow().len()), 0);
+    }
+
+    #[test]
+    fn discard_while_settling() {
+        let ctx = JSContext::default();
+        let other = JSContext::default();
+        let settled = Rc::new(RefCell::new(false));
+
+        // like a reaction destroying the view of the other promise
+        let other_ctx = other.raw;
+        let s = settled.clone();
+        PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            pending.push(Pending {
+                id: u64::MAX - 1,
+                ctx: ctx.raw,
+                settle: Box::new(move || {
+                    discard_pending_promises_where(|pending| pending == other_ctx);
+                    true
+                }),
+            });
+            pending.push(Pending {
+                id: u64::MAX,
+                ctx: other.raw,
+                settle: Box::new(move || {
+                    *s.borrow_mut() = true;
+                    true
+                }),
+            });
+        });
+
+        settle_pending_promises();
+        assert!(!*settled.borrow());
+        assert_eq!(PENDING.with(|pending| pending.borrow().len()), 0);
+    }
+}
@@ -0,0 +1,144 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{JSContext, JSException, JSObject, JSValue};
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Values that can be kept alive by `Protected`.
+pub trait AsJSValue {
+    fn as_js_value(&self) -> &JSValue;
+}
+
+impl AsJSValue for JSValue {
+    fn as_js_value(&self) -> &JSValue {
+        self
+    }
+}
+
+impl AsJSValue for JSObject {
+    fn as_js_value(&self) -> &JSValue {
+        &self.value
+    }
+}
+
+impl AsJSValue for JSException {
+    fn as_js_value(&self) -> &JSValue {
+        &self.value
+    }
+}
+
+/// A value that is protected from garbage collection, and whose context is
+/// retained, until dropped.
+///
+/// Values are otherwise only kept alive while they are referenced from
+/// JavaScript or the native stack, so `Protected` is needed to hold on to
+/// them anywhere else, eg. after unlocking the context of a view.
+///
+/// The lifetime bounds the wrapper by whatever keeps the context itself
+/// valid, eg. the renderer of a view, as releasing it afterwards would
+/// use a context that was destroyed.
+///
+/// ```
+/// # use javascriptcore::*;
+/// let ctx = JSContext::default();
+///
+/// let value = Protected::new(JSValue::new_string(&ctx, "abc"));
+/// garbage_collect(&ctx);
+/// assert_eq!(value.as_string().unwrap(), "abc");
+/// ```
+pub struct Protected<'a, T: AsJSValue> {
+    inner: T,
+    ctx: JSContext,
+
+    phantom: PhantomData<&'a ()>,
+}
+
+impl<'a, T: AsJSValue> Protected<'a, T> {
+    /// Protects `inner` until the returned wrapper is dropped.
+    pub fn new(inner: T) -> Self {
+        let value = inner.as_js_value();
+
+        let ctx = unsafe {
+            if !value.raw.is_null() {
+                ul_sys::JSValueProtect(value.ctx, value.raw);
+            }
+
+            JSContext::from_context_ref(value.ctx)
+        };
+
+        Protected {
+            inner,
+            ctx,
+
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: AsJSValue> Deref for Protected<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<'a, T: AsJSValue> Drop for Protected<'a, T> {
+    fn drop(&mut self) {
+        let raw = self.inner.as_js_value().raw;
+
+        if !raw.is_null() {
+            unsafe { ul_sys::JSValueUnprotect(self.ctx.raw, raw) };
+        }
+    }
+}
+
+impl<'a, T: AsJSValue + fmt::Debug> fmt::Debug for Protected<'a, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(fmt)
+    }
+}
+
+impl<'a, T: AsJSValue + fmt::Display> fmt::Display for Protected<'a, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(fmt)
+    }
+}
+
+impl<'a, T: AsJSValue + Error> Error for Protected<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{evaluate_script, garbage_collect, JSContext};
+    use super::Protected;
+
+    #[test]
+    fn survives_garbage_collection() {
+        let ctx = JSContext::default();
+
+        let objects: Vec<_> = (0..100)
+            .map(|i| {
+                let value = evaluate_script(&ctx, format!("({{ n: {} }})", i), None, "test.js", 1);
+                Protected::new(value.expect("object").as_object().expect("object"))
+            })
+            .collect();
+
+        garbage_collect(&ctx);
+
+        for (i, object) in objects.iter().enumerate() {
+            let n = object.get_property("n").expect("value");
+            assert_eq!(n.as_number().expect("number"), i as f64);
+        }
+
+        let e = evaluate_script(&ctx, "throw new Error('abc')", None, "test.js", 1);
+        let e = Protected::new(e.unwrap_err());
+        garbage_collect(&ctx);
+        assert_eq!(e.to_string(), "abc");
+    }
+}
@@ -0,0 +1,960 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{JSContext, JSException, JSObject, JSString, JSValue};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use serde::forward_to_deserialize_any;
+use std::{fmt, ptr};
+
+/// An error converting between Rust and JavaScript values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerdeError(String);
+
+impl SerdeError {
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError(msg.to_string())
+    }
+}
+
+impl From<JSException> for SerdeError {
+    fn from(e: JSException) -> Self {
+        SerdeError(e.message())
+    }
+}
+
+/// Converts a Rust value into a JavaScript value.
+///
+/// Structs and maps become objects, sequences and tuples arrays, byte
+/// buffers `Uint8Array`s and `None` and `()` `null`. Enums are represented
+/// like `serde_json` does, eg. `{"Variant": value}`. Map keys have to be
+/// strings or numbers.
+///
+/// ```
+/// # use javascriptcore::*;
+/// let ctx = JSContext::default();
+///
+/// let v = to_value(&ctx, &vec![(1, "a"), (2, "b")]).expect("value");
+/// assert_eq!(v.to_json_string(0).expect("json"), "[[1,\"a\"],[2,\"b\"]]");
+/// ```
+pub fn to_value<T>(ctx: &JSContext, value: &T) -> Result<JSValue, SerdeError>
+    where
+        T: Serialize + ?Sized,
+{
+    value.serialize(Serializer { ctx: ctx.raw })
+}
+
+/// Converts a JavaScript value into a Rust value.
+///
+/// Objects are read through their enumerable properties, arrays through
+/// their indexes and typed arrays and `ArrayBuffer`s as their bytes, so
+/// they can be deserialized as `Vec<u8>` or as a byte buffer.
+///
+/// ```
+/// # use javascriptcore::*;
+/// # use std::collections::HashMap;
+/// let ctx = JSContext::default();
+///
+/// let v = JSValue::new_from_json(&ctx, "{\"a\": [1, 2]}").expect("valid object");
+/// let map: HashMap<String, Vec<u32>> = from_value(&v).expect("map");
+/// assert_eq!(map["a"], vec![1, 2]);
+/// ```
+pub fn from_value<T>(value: &JSValue) -> Result<T, SerdeError>
+    where
+        T: DeserializeOwned,
+{
+    T::deserialize(Deserializer::new(value.ctx, value.raw, 0))
+}
+
+fn check_exception(ctx: ul_sys::JSContextRef, e: ul_sys::JSValueRef) -> Result<(), SerdeError> {
+    if e.is_null() {
+        Ok(())
+    } else {
+        Err(JSException {
+            value: JSValue { raw: e, ctx },
+        }
+        .into())
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Serializer {
+    ctx: ul_sys::JSContextRef,
+}
+
+impl Serializer {
+    fn value(self, raw: ul_sys::JSValueRef) -> JSValue {
+        JSValue { raw, ctx: self.ctx }
+    }
+
+    fn number(self, n: f64) -> Result<JSValue, SerdeError> {
+        Ok(self.value(unsafe { ul_sys::JSValueMakeNumber(self.ctx, n) }))
+    }
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    type SerializeSeq = Compound;
+    type SerializeTuple = Compound;
+    type SerializeTupleStruct = Compound;
+    type SerializeTupleVariant = Compound;
+    type SerializeMap = Compound;
+    type SerializeStruct = Compound;
+    type SerializeStructVariant = Compound;
+
+    fn serialize_bool(self, v: bool) -> Result<JSValue, SerdeError> {
+        Ok(self.value(unsafe { ul_sys::JSValueMakeBoolean(self.ctx, v) }))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<JSValue, SerdeError> {
+        self.number(v as f64)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<JSValue, SerdeError> {
+        self.number(v as f64)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<JSValue, SerdeError> {
+        self.number(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<JSValue, SerdeError> {
+        self.number(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<JSValue, SerdeError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<JSValue, SerdeError> {
+        let string = JSString::from(v);
+
+        Ok(self.value(unsafe { ul_sys::JSValueMakeString(self.ctx, string.raw) }))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<JSValue, SerdeError> {
+        unsafe {
+            let mut e: ul_sys::JSValueRef = ptr::null_mut();
+            let array = ul_sys::JSObjectMakeTypedArray(
+                self.ctx,
+                ul_sys::JSTypedArrayType_kJSTypedArrayTypeUint8Array,
+                v.len() as _,
+                &mut e,
+            );
+            check_exception(self.ctx, e)?;
+
+            if !v.is_empty() {
+                let bytes = ul_sys::JSObjectGetTypedArrayBytesPtr(self.ctx, array, &mut e);
+                check_exception(self.ctx, e)?;
+
+                ptr::copy_nonoverlapping(v.as_ptr(), bytes as *mut u8, v.len());
+            }
+
+            Ok(self.value(array))
+        }
+    }
+
+    fn serialize_none(self) -> Result<JSValue, SerdeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JSValue, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JSValue, SerdeError> {
+        Ok(self.value(unsafe { ul_sys::JSValueMakeNull(self.ctx) }))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JSValue, SerdeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<JSValue, SerdeError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JSValue, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JSValue, SerdeError> {
+        let mut object = Compound::object(self.ctx, None);
+        object.set(&JSString::from(variant), value)?;
+        object.finish()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound, SerdeError> {
+        Compound::array(self.ctx, None)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound, SerdeError> {
+        Compound::array(self.ctx, None)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound, SerdeError> {
+        Compound::array(self.ctx, None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound, SerdeError> {
+        Compound::array(self.ctx, Some(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound, SerdeError> {
+        Ok(Compound::object(self.ctx, None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound, SerdeError> {
+        Ok(Compound::object(self.ctx, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound, SerdeError> {
+        Ok(Compound::object(self.ctx, Some(variant)))
+    }
+}
+
+/// An array or object being filled by a serializer.
+///
+/// The object is protected from garbage collection until the compound is
+/// dropped, as it is only referenced from Rust while being built.
+struct Compound {
+    ctx: ul_sys::JSContextRef,
+    object: ul_sys::JSObjectRef,
+    // next index of an array
+    index: u32,
+    // key of the map entry whose value is serialized next
+    key: Option<JSString>,
+    // variant of an enum to wrap the finished object in
+    variant: Option<&'static str>,
+}
+
+impl Compound {
+    fn new(
+        ctx: ul_sys::JSContextRef,
+        object: ul_sys::JSObjectRef,
+        variant: Option<&'static str>,
+    ) -> Compound {
+        unsafe { ul_sys::JSValueProtect(ctx, object) };
+
+        Compound {
+            ctx,
+            object,
+            index: 0,
+            key: None,
+            variant,
+        }
+    }
+
+    fn array(
+        ctx: ul_sys::JSContextRef,
+        variant: Option<&'static str>,
+    ) -> Result<Compound, SerdeError> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let array = unsafe { ul_sys::JSObjectMakeArray(ctx, 0, ptr::null(), &mut e) };
+        check_exception(ctx, e)?;
+
+        Ok(Compound::new(ctx, array, variant))
+    }
+
+    fn object(ctx: ul_sys::JSContextRef, variant: Option<&'static str>) -> Compound {
+        let object = unsafe { ul_sys::JSObjectMake(ctx, ptr::null_mut(), ptr::null_mut()) };
+
+        Compound::new(ctx, object, variant)
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let value = value.serialize(Serializer { ctx: self.ctx })?;
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        unsafe {
+            ul_sys::JSObjectSetPropertyAtIndex(self.ctx, self.object, self.index, value.raw, &mut e)
+        };
+        check_exception(self.ctx, e)?;
+
+        self.index += 1;
+
+        Ok(())
+    }
+
+    fn set<T: Serialize + ?Sized>(&mut self, key: &JSString, value: &T) -> Result<(), SerdeError> {
+        let value = value.serialize(Serializer { ctx: self.ctx })?;
+
+        self.set_raw(key, value.raw)
+    }
+
+    fn set_raw(&mut self, key: &JSString, value: ul_sys::JSValueRef) -> Result<(), SerdeError> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        unsafe { ul_sys::JSObjectSetProperty(self.ctx, self.object, key.raw, value, 0, &mut e) };
+        check_exception(self.ctx, e)
+    }
+
+    fn finish(self) -> Result<JSValue, SerdeError> {
+        let variant = match self.variant {
+            Some(variant) => variant,
+            None => {
+                return Ok(JSValue {
+                    raw: self.object,
+                    ctx: self.ctx,
+                })
+            }
+        };
+
+        let mut wrapper = Compound::object(self.ctx, None);
+        wrapper.set_raw(&JSString::from(variant), self.object)?;
+        wrapper.finish()
+    }
+}
+
+impl Drop for Compound {
+    fn drop(&mut self) {
+        unsafe { ul_sys::JSValueUnprotect(self.ctx, self.object) };
+    }
+}
+
+impl ser::SerializeSeq for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        let key = key.serialize(Serializer { ctx: self.ctx })?;
+
+        if !key.is_string() && !key.is_number() {
+            return Err(SerdeError("map keys must be strings or numbers".to_owned()));
+        }
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let string = unsafe { ul_sys::JSValueToStringCopy(self.ctx, key.raw, &mut e) };
+        check_exception(self.ctx, e)?;
+
+        self.key = Some(JSString { raw: string });
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| SerdeError("map value serialized before its key".to_owned()))?;
+
+        self.set(&key, value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.set(&JSString::from(key), value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound {
+    type Ok = JSValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.set(&JSString::from(key), value)
+    }
+
+    fn end(self) -> Result<JSValue, SerdeError> {
+        self.finish()
+    }
+}
+
+// Objects and arrays nested deeper than this are rejected instead of
+// overflowing the stack, which is what cyclic values would do otherwise.
+const MAX_DEPTH: u32 = 128;
+
+struct Deserializer {
+    value: JSValue,
+    // number of objects and arrays the value is nested in
+    depth: u32,
+}
+
+impl Deserializer {
+    fn new(ctx: ul_sys::JSContextRef, raw: ul_sys::JSValueRef, depth: u32) -> Deserializer {
+        Deserializer {
+            value: JSValue { raw, ctx },
+            depth,
+        }
+    }
+
+    /// Returns the depth of the properties or elements of the value.
+    fn nested_depth(&self) -> Result<u32, SerdeError> {
+        if self.depth >= MAX_DEPTH {
+            Err(SerdeError(format!(
+                "value nested deeper than {} levels, it may be cyclic",
+                MAX_DEPTH
+            )))
+        } else {
+            Ok(self.depth + 1)
+        }
+    }
+
+    /// Returns the value as an object if it is a typed array or an
+    /// `ArrayBuffer`.
+    fn typed_array(&self) -> Result<Option<JSObject>, SerdeError> {
+        if self.value.get_typed_array_type()? == ul_sys::JSTypedArrayType_kJSTypedArrayTypeNone {
+            Ok(None)
+        } else {
+            Ok(Some(self.value.as_object()?))
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let value = &self.value;
+
+        if value.is_undefined() || value.is_null() {
+            visitor.visit_unit()
+        } else if value.is_boolean() {
+            visitor.visit_bool(value.as_boolean())
+        } else if value.is_number() {
+            let n = value.as_number().unwrap_or(f64::NAN);
+
+            // the MAX of both types rounds up to the next power of two as f64,
+            // which is out of range
+            if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+                visitor.visit_i64(n as i64)
+            } else if n.fract() == 0.0 && n >= 0.0 && n < u64::MAX as f64 {
+                visitor.visit_u64(n as u64)
+            } else {
+                visitor.visit_f64(n)
+            }
+        } else if value.is_string() {
+            visitor.visit_string(value.as_string()?.to_string())
+        } else if value.is_object() {
+            if let Some(array) = self.typed_array()? {
+                let bytes = array.bytes()?;
+
+                return visitor.visit_seq(de::value::SeqDeserializer::new(bytes.iter().copied()));
+            }
+
+            let object = value.as_object()?;
+            let depth = self.nested_depth()?;
+
+            if value.is_array() {
+                let len = object.get_property("length")?.as_number()?;
+
+                visitor.visit_seq(ArrayAccess {
+                    ctx: value.ctx,
+                    object: object.raw,
+                    index: 0,
+                    len: len as u32,
+                    depth,
+                })
+            } else {
+                visitor.visit_map(ObjectAccess::new(value.ctx, object.raw, depth))
+            }
+        } else {
+            Err(SerdeError("unsupported value type".to_owned()))
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        if self.value.is_undefined() || self.value.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.typed_array()? {
+            Some(array) => visitor.visit_bytes(array.bytes()?),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let value = &self.value;
+
+        if value.is_string() {
+            let variant = value.as_string()?.to_string();
+
+            return visitor.visit_enum(IntoDeserializer::<SerdeError>::into_deserializer(variant));
+        }
+
+        if value.is_object() {
+            let object = value.as_object()?;
+            let depth = self.nested_depth()?;
+            let mut properties = ObjectAccess::new(value.ctx, object.raw, depth);
+
+            if properties.count == 1 {
+                let variant: String = de::MapAccess::next_key(&mut properties)?
+                    .ok_or_else(|| SerdeError("expected a variant".to_owned()))?;
+                let name = properties.name.take().expect("name of the variant");
+
+                let mut e: ul_sys::JSValueRef = ptr::null_mut();
+                let content =
+                    unsafe { ul_sys::JSObjectGetProperty(value.ctx, object.raw, name.raw, &mut e) };
+                check_exception(value.ctx, e)?;
+
+                return visitor.visit_enum(EnumAccess {
+                    variant,
+                    value: JSValue {
+                        raw: content,
+                        ctx: value.ctx,
+                    },
+                    depth,
+                });
+            }
+        }
+
+        Err(SerdeError(
+            "expected a string or an object with a single property".to_owned(),
+        ))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ArrayAccess {
+    ctx: ul_sys::JSContextRef,
+    object: ul_sys::JSObjectRef,
+    index: u32,
+    len: u32,
+    depth: u32,
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let element =
+            unsafe { ul_sys::JSObjectGetPropertyAtIndex(self.ctx, self.object, self.index, &mut e) };
+        check_exception(self.ctx, e)?;
+
+        self.index += 1;
+
+        seed.deserialize(Deserializer::new(self.ctx, element, self.depth)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+struct ObjectAccess {
+    ctx: ul_sys::JSContextRef,
+    object: ul_sys::JSObjectRef,
+    names: ul_sys::JSPropertyNameArrayRef,
+    index: usize,
+    count: usize,
+    // name of the property whose value is deserialized next
+    name: Option<JSString>,
+    depth: u32,
+}
+
+impl ObjectAccess {
+    fn new(ctx: ul_sys::JSContextRef, object: ul_sys::JSObjectRef, depth: u32) -> ObjectAccess {
+        let names = unsafe { ul_sys::JSObjectCopyPropertyNames(ctx, object) };
+
+        ObjectAccess {
+            ctx,
+            object,
+            names,
+            index: 0,
+            count: unsafe { ul_sys::JSPropertyNameArrayGetCount(names) } as usize,
+            name: None,
+            depth,
+        }
+    }
+}
+
+impl Drop for ObjectAccess {
+    fn drop(&mut self) {
+        unsafe { ul_sys::JSPropertyNameArrayRelease(self.names) };
+    }
+}
+
+impl<'de> de::MapAccess<'de> for ObjectAccess {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        if self.index >= self.count {
+            return Ok(None);
+        }
+
+        // the names are owned by the array, so retain the one kept around
+        let name = unsafe {
+            ul_sys::JSStringRetain(ul_sys::JSPropertyNameArrayGetNameAtIndex(
+                self.names,
+                self.index as _,
+            ))
+        };
+        let name = JSString { raw: name };
+
+        self.index += 1;
+
+        let key = seed.deserialize(IntoDeserializer::<SerdeError>::into_deserializer(
+            name.to_string(),
+        ))?;
+
+        self.name = Some(name);
+
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, SerdeError> {
+        let name = self
+            .name
+            .take()
+            .ok_or_else(|| SerdeError("map value deserialized before its key".to_owned()))?;
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let value = unsafe { ul_sys::JSObjectGetProperty(self.ctx, self.object, name.raw, &mut e) };
+        check_exception(self.ctx, e)?;
+
+        seed.deserialize(Deserializer::new(self.ctx, value, self.depth))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.count - self.index)
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    value: JSValue,
+    depth: u32,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = SerdeError;
+    type Variant = Deserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer), SerdeError> {
+        let variant =
+            seed.deserialize(IntoDeserializer::<SerdeError>::into_deserializer(self.variant))?;
+
+        Ok((
+            variant,
+            Deserializer {
+                value: self.value,
+                depth: self.depth,
+            },
+        ))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, SerdeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{evaluate_script, JSContext, JSValue};
+    use super::{from_value, to_value};
+    use serde::de::IgnoredAny;
+    use serde::{Serialize, Serializer};
+    use std::collections::HashMap;
+
+    struct Bytes<'a>(&'a [u8]);
+
+    impl<'a> Serialize for Bytes<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    #[test]
+    fn to_value_json() {
+        let ctx = JSContext::default();
+
+        let v = to_value(&ctx, &(1u8, "a", true, None::<f64>, 1.5)).unwrap();
+        assert_eq!(v.to_json_string(0).unwrap(), "[1,\"a\",true,null,1.5]");
+
+        let mut map = HashMap::new();
+        map.insert(1, vec![2.0]);
+        let v = to_value(&ctx, &map).unwrap();
+        assert_eq!(v.to_json_string(0).unwrap(), "{\"1\":[2]}");
+
+        let v = to_value(&ctx, &Err::<u32, String>("abc".to_owned())).unwrap();
+        assert_eq!(v.to_json_string(0).unwrap(), "{\"Err\":\"abc\"}");
+    }
+
+    #[test]
+    fn to_value_bytes() {
+        let ctx = JSContext::default();
+
+        let v = to_value(&ctx, &Bytes(&[1, 2, 3])).unwrap();
+        let o = v.as_object().unwrap();
+        assert!(o.get_property("BYTES_PER_ELEMENT").unwrap().is_number());
+        assert_eq!(o.get_property_at_index(2).unwrap().as_number().unwrap(), 3.0);
+    }
+
+    #[test]
+    fn from_value_json() {
+        let ctx = JSContext::default();
+
+        let v = JSValue::new_from_json(&ctx, "{\"a\": [1, 2.5], \"b\": []}").unwrap();
+        let map: HashMap<String, Vec<f64>> = from_value(&v).unwrap();
+        assert_eq!(map["a"], vec![1.0, 2.5]);
+        assert!(map["b"].is_empty());
+
+        let v = JSValue::new_from_json(&ctx, "[\"x\", null, 3]").unwrap();
+        let t: (String, Option<u8>, u64) = from_value(&v).unwrap();
+        assert_eq!(t, ("x".to_owned(), None, 3));
+
+        let v = JSValue::new_from_json(&ctx, "{\"Ok\": 3}").unwrap();
+        assert_eq!(from_value::<Result<u32, String>>(&v).unwrap(), Ok(3));
+
+        assert!(from_value::<u8>(&JSValue::new_number(&ctx, -1.0)).is_err());
+        let two_pow_63 = JSValue::new_number(&ctx, 9223372036854775808.0);
+        assert_eq!(from_value::<u64>(&two_pow_63).unwrap(), 1 << 63);
+        assert!(from_value::<u64>(&JSValue::new_number(&ctx, 18446744073709551616.0)).is_err());
+        assert!(from_value::<String>(&JSValue::new_number(&ctx, 1.0)).is_err());
+    }
+
+    #[test]
+    fn from_value_typed_arrays() {
+        let ctx = JSContext::default();
+
+        let v = evaluate_script(&ctx, "new Uint8Array([1, 2, 3])", None, "test.js", 1).unwrap();
+        assert_eq!(from_value::<Vec<u8>>(&v).unwrap(), vec![1, 2, 3]);
+
+        let v = evaluate_script(&ctx, "new Uint16Array([1, 256]).buffer", None, "test.js", 1)
+            .unwrap();
+        assert_eq!(from_value::<Vec<u8>>(&v).unwrap().len(), 4);
+
+        let v = evaluate_script(&ctx, "new Uint8Array([1, 2, 3]).subarray(1)", None, "test.js", 1)
+            .unwrap();
+        assert_eq!(from_value::<Vec<u8>>(&v).unwrap(), vec![2, 3]);
+    }
+
+    #[test]
+    fn from_value_cyclic() {
+        let ctx = JSContext::default();
+
+        let v = evaluate_script(&ctx, "var a = { b: {} }; a.b.a = a; a", None, "test.js", 1)
+            .unwrap();
+        let e = from_value::<IgnoredAny>(&v).unwrap_err();
+        assert!(e.message().contains("cyclic"));
+
+        let v = evaluate_script(&ctx, "var l = [1]; l.push(l); l", None, "test.js", 1).unwrap();
+        assert!(from_value::<IgnoredAny>(&v).is_err());
+
+        let v = evaluate_script(&ctx, "var s = {}; [s, s]", None, "test.js", 1).unwrap();
+        assert!(from_value::<IgnoredAny>(&v).is_ok());
+    }
+
+    #[test]
+    fn round_trip() {
+        let ctx = JSContext::default();
+
+        let mut map = HashMap::new();
+        map.insert("key".to_owned(), (vec![1u32, 2], Some("x".to_owned())));
+
+        let v = to_value(&ctx, &map).unwrap();
+        let back: HashMap<String, (Vec<u32>, Option<String>)> = from_value(&v).unwrap();
+        assert_eq!(back, map);
+    }
+}
@@ -73,19 +73,24 @@ impl PartialEq<JSString> for String {
 
 impl<'s> From<&'s str> for JSString {
     fn from(s: &'s str) -> Self {
-        let c = CString::new(s.as_bytes()).unwrap();
+        // Going through UTF16 rather than a C string keeps
+        // interior NUL characters intact.
+        let chars: Vec<ul_sys::JSChar> = s.encode_utf16().collect();
         JSString {
-            raw: unsafe { ul_sys::JSStringCreateWithUTF8CString(c.as_ptr()) },
+            raw: unsafe { ul_sys::JSStringCreateWithCharacters(chars.as_ptr(), chars.len() as _) },
         }
     }
 }
 
+impl<'s> From<&'s String> for JSString {
+    fn from(s: &'s String) -> Self {
+        s.as_str().into()
+    }
+}
+
 impl From<String> for JSString {
     fn from(s: String) -> Self {
-        let c = CString::new(s.as_bytes()).unwrap();
-        JSString {
-            raw: unsafe { ul_sys::JSStringCreateWithUTF8CString(c.as_ptr()) },
-        }
+        s.as_str().into()
     }
 }
 
@@ -115,6 +120,10 @@ mod tests {
 
         let e: String = (&d).into();
         assert_eq!(e, "abcdef");
+
+        let f = "abc".to_owned();
+        let g: JSString = (&f).into();
+        assert_eq!(g, a);
     }
 
     #[test]
@@ -0,0 +1,322 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{JSContext, JSException, JSObject, JSTypedArrayType, JSValue};
+use std::os::raw::c_void;
+use std::{mem, ptr, slice};
+
+/// Rust types that can be the elements of a typed array.
+pub trait TypedArrayElement: Copy + 'static {
+    /// The type of typed array holding elements of this type.
+    const TYPE: JSTypedArrayType;
+}
+
+macro_rules! typed_array_element {
+    ($($t:ty => $kind:ident),*) => {
+        $(
+            impl TypedArrayElement for $t {
+                const TYPE: JSTypedArrayType = ul_sys::$kind;
+            }
+        )*
+    };
+}
+
+typed_array_element!(
+    i8 => JSTypedArrayType_kJSTypedArrayTypeInt8Array,
+    i16 => JSTypedArrayType_kJSTypedArrayTypeInt16Array,
+    i32 => JSTypedArrayType_kJSTypedArrayTypeInt32Array,
+    u8 => JSTypedArrayType_kJSTypedArrayTypeUint8Array,
+    u16 => JSTypedArrayType_kJSTypedArrayTypeUint16Array,
+    u32 => JSTypedArrayType_kJSTypedArrayTypeUint32Array,
+    f32 => JSTypedArrayType_kJSTypedArrayTypeFloat32Array,
+    f64 => JSTypedArrayType_kJSTypedArrayTypeFloat64Array
+);
+
+// Drops the boxed slice passed as `context` to a no-copy constructor once
+// JavaScript releases the buffer.
+unsafe extern "C" fn drop_boxed_slice<T>(_bytes: *mut c_void, context: *mut c_void) {
+    drop(Box::from_raw(context as *mut Box<[T]>));
+}
+
+impl JSObject {
+    fn from_raw_result(
+        ctx: &JSContext,
+        raw: ul_sys::JSObjectRef,
+        e: ul_sys::JSValueRef,
+    ) -> Result<Self, JSException> {
+        if raw.is_null() || !e.is_null() {
+            Err(JSException {
+                value: JSValue { raw: e, ctx: ctx.raw },
+            })
+        } else {
+            Ok(JSObject {
+                raw,
+                value: JSValue { raw, ctx: ctx.raw },
+            })
+        }
+    }
+
+    /// Creates a zero-filled typed array of `length` elements.
+    ///
+    /// * `kind`: The type of typed array to create. Must not be
+    ///   `kJSTypedArrayTypeArrayBuffer` or `kJSTypedArrayTypeNone`.
+    pub fn new_typed_array(
+        ctx: &JSContext,
+        kind: JSTypedArrayType,
+        length: usize,
+    ) -> Result<Self, JSException> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let raw = unsafe { ul_sys::JSObjectMakeTypedArray(ctx.raw, kind, length as _, &mut e) };
+        JSObject::from_raw_result(ctx, raw, e)
+    }
+
+    /// Creates a typed array holding a copy of `items`, eg. a
+    /// `Float32Array` for a slice of `f32`.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let a = JSObject::new_typed_array_from_slice(&ctx, &[1.5f32, 2.5]).expect("array");
+    ///
+    /// assert_eq!(a.get_property_at_index(1).expect("value").as_number().expect("number"), 2.5);
+    /// ```
+    pub fn new_typed_array_from_slice<T: TypedArrayElement>(
+        ctx: &JSContext,
+        items: &[T],
+    ) -> Result<Self, JSException> {
+        let array = JSObject::new_typed_array(ctx, T::TYPE, items.len())?;
+
+        unsafe {
+            let mut e: ul_sys::JSValueRef = ptr::null_mut();
+            let bytes = ul_sys::JSObjectGetTypedArrayBytesPtr(ctx.raw, array.raw, &mut e);
+            array.check_exception(e)?;
+
+            if !items.is_empty() {
+                ptr::copy_nonoverlapping(items.as_ptr(), bytes as *mut T, items.len());
+            }
+        }
+
+        Ok(array)
+    }
+
+    /// Creates a typed array backed by `items` without copying them.
+    ///
+    /// The items are dropped once the array is garbage collected.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let samples: Vec<f64> = (0..1000).map(f64::from).collect();
+    /// let a = JSObject::new_typed_array_no_copy(&ctx, samples).expect("array");
+    ///
+    /// assert_eq!(a.get_property("length").expect("value").as_number().expect("number"), 1000.0);
+    /// ```
+    pub fn new_typed_array_no_copy<T, I>(ctx: &JSContext, items: I) -> Result<Self, JSException>
+        where
+            T: TypedArrayElement,
+            I: Into<Box<[T]>>,
+    {
+        let mut items = Box::new(items.into());
+        let bytes = items.as_mut_ptr() as *mut c_void;
+        let byte_length = items.len() * mem::size_of::<T>();
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let raw = unsafe {
+            ul_sys::JSObjectMakeTypedArrayWithBytesNoCopy(
+                ctx.raw,
+                T::TYPE,
+                bytes,
+                byte_length as _,
+                Some(drop_boxed_slice::<T>),
+                Box::into_raw(items) as *mut c_void,
+                &mut e,
+            )
+        };
+        JSObject::from_raw_result(ctx, raw, e)
+    }
+
+    /// Creates an `ArrayBuffer` holding a copy of `bytes`.
+    pub fn new_array_buffer(ctx: &JSContext, bytes: &[u8]) -> Result<Self, JSException> {
+        let array = JSObject::new_typed_array_from_slice(ctx, bytes)?;
+        array.typed_array_buffer()
+    }
+
+    /// Creates an `ArrayBuffer` backed by `bytes` without copying them.
+    ///
+    /// The bytes are dropped once the buffer is garbage collected.
+    pub fn new_array_buffer_no_copy<I>(ctx: &JSContext, bytes: I) -> Result<Self, JSException>
+        where
+            I: Into<Box<[u8]>>,
+    {
+        let mut bytes = Box::new(bytes.into());
+        let data = bytes.as_mut_ptr() as *mut c_void;
+        let byte_length = bytes.len();
+
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let raw = unsafe {
+            ul_sys::JSObjectMakeArrayBufferWithBytesNoCopy(
+                ctx.raw,
+                data,
+                byte_length as _,
+                Some(drop_boxed_slice::<u8>),
+                Box::into_raw(bytes) as *mut c_void,
+                &mut e,
+            )
+        };
+        JSObject::from_raw_result(ctx, raw, e)
+    }
+
+    /// Returns the number of elements of a typed array.
+    pub fn typed_array_length(&self) -> Result<usize, JSException> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let length = unsafe { ul_sys::JSObjectGetTypedArrayLength(self.value.ctx, self.raw, &mut e) };
+        self.check_typed_array(e)?;
+        Ok(length as usize)
+    }
+
+    /// Returns the `ArrayBuffer` backing a typed array.
+    pub fn typed_array_buffer(&self) -> Result<JSObject, JSException> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let raw = unsafe { ul_sys::JSObjectGetTypedArrayBuffer(self.value.ctx, self.raw, &mut e) };
+        self.check_typed_array(e)?;
+        Ok(JSObject {
+            raw,
+            value: JSValue {
+                raw,
+                ctx: self.value.ctx,
+            },
+        })
+    }
+
+    /// Borrows the bytes of a typed array or an `ArrayBuffer`.
+    ///
+    /// For typed arrays, only the bytes viewed by the array are returned.
+    /// Fails with a `TypeError` for other objects.
+    ///
+    /// The bytes must not be used after the buffer is detached, eg. by
+    /// transferring it to a worker.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    /// let v = evaluate_script(&ctx, "new Uint8Array([1, 2, 3])", None, "test.js", 1)
+    ///     .expect("value");
+    /// let a = v.as_object().expect("object");
+    ///
+    /// assert_eq!(a.bytes().expect("bytes"), &[1, 2, 3]);
+    /// ```
+    pub fn bytes(&self) -> Result<&[u8], JSException> {
+        let (data, len) = self.raw_bytes()?;
+
+        if data.is_null() || len == 0 {
+            Ok(&[])
+        } else {
+            Ok(unsafe { slice::from_raw_parts(data, len) })
+        }
+    }
+
+    /// Mutably borrows the bytes of a typed array or an `ArrayBuffer`.
+    ///
+    /// See `bytes`.
+    pub fn bytes_mut(&mut self) -> Result<&mut [u8], JSException> {
+        let (data, len) = self.raw_bytes()?;
+
+        if data.is_null() || len == 0 {
+            Ok(&mut [])
+        } else {
+            Ok(unsafe { slice::from_raw_parts_mut(data, len) })
+        }
+    }
+
+    fn raw_bytes(&self) -> Result<(*mut u8, usize), JSException> {
+        let ctx = self.value.ctx;
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+
+        let (data, len) = match self.get_typed_array_type()? {
+            ul_sys::JSTypedArrayType_kJSTypedArrayTypeNone => {
+                return Err(self.type_error("object is not a typed array or ArrayBuffer"));
+            }
+            ul_sys::JSTypedArrayType_kJSTypedArrayTypeArrayBuffer => unsafe {
+                (
+                    ul_sys::JSObjectGetArrayBufferBytesPtr(ctx, self.raw, &mut e),
+                    ul_sys::JSObjectGetArrayBufferByteLength(ctx, self.raw, &mut e),
+                )
+            },
+            _ => unsafe {
+                (
+                    ul_sys::JSObjectGetTypedArrayBytesPtr(ctx, self.raw, &mut e),
+                    ul_sys::JSObjectGetTypedArrayByteLength(ctx, self.raw, &mut e),
+                )
+            },
+        };
+        self.check_exception(e)?;
+
+        Ok((data as *mut u8, len as usize))
+    }
+
+    fn check_typed_array(&self, e: ul_sys::JSValueRef) -> Result<(), JSException> {
+        self.check_exception(e)?;
+
+        match self.get_typed_array_type()? {
+            ul_sys::JSTypedArrayType_kJSTypedArrayTypeNone
+            | ul_sys::JSTypedArrayType_kJSTypedArrayTypeArrayBuffer => {
+                Err(self.type_error("object is not a typed array"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{evaluate_script, JSContext, JSObject};
+
+    #[test]
+    fn can_make_typed_arrays() {
+        let ctx = JSContext::default();
+
+        let a = JSObject::new_typed_array_from_slice(&ctx, &[1u16, 256]).expect("array");
+        assert_eq!(
+            a.get_typed_array_type().expect("type"),
+            ul_sys::JSTypedArrayType_kJSTypedArrayTypeUint16Array
+        );
+        assert_eq!(a.typed_array_length().expect("length"), 2);
+        assert_eq!(a.bytes().expect("bytes").len(), 4);
+
+        let mut a = JSObject::new_typed_array_no_copy(&ctx, vec![1.5f32, 2.5]).expect("array");
+        a.bytes_mut().expect("bytes")[..4].copy_from_slice(&3.5f32.to_ne_bytes());
+        assert_eq!(a.get_property_at_index(0).expect("value").as_number().expect("number"), 3.5);
+    }
+
+    #[test]
+    fn can_make_array_buffers() {
+        let ctx = JSContext::default();
+
+        let b = JSObject::new_array_buffer(&ctx, &[1, 2, 3]).expect("buffer");
+        assert_eq!(
+            b.get_typed_array_type().expect("type"),
+            ul_sys::JSTypedArrayType_kJSTypedArrayTypeArrayBuffer
+        );
+        assert_eq!(b.bytes().expect("bytes"), &[1, 2, 3]);
+
+        let b = JSObject::new_array_buffer_no_copy(&ctx, vec![4u8, 5]).expect("buffer");
+        assert_eq!(b.bytes().expect("bytes"), &[4, 5]);
+        assert!(b.typed_array_length().is_err());
+    }
+
+    #[test]
+    fn borrows_viewed_bytes() {
+        let ctx = JSContext::default();
+
+        let script = "new Uint8Array([1, 2, 3, 4]).subarray(1, 3)";
+        let v = evaluate_script(&ctx, script, None, "test.js", 1).expect("value");
+        let a = v.as_object().expect("object");
+        assert_eq!(a.bytes().expect("bytes"), &[2, 3]);
+        assert_eq!(a.typed_array_buffer().expect("buffer").bytes().expect("bytes").len(), 4);
+
+        assert!(JSObject::new(&ctx).bytes().is_err());
+    }
+}
@@ -4,7 +4,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::{JSClass, JSContext, JSException, JSObject, JSString, JSType, JSValue};
+use super::{
+    JSClass, JSContext, JSException, JSObject, JSString, JSType, JSTypedArrayType, JSValue,
+};
+use crate::Error;
 use std::ptr;
 
 impl JSValue {
@@ -127,8 +130,8 @@ impl JSValue {
     /// * `string`: A value that can be converted into a [`JSString`] containing
     ///   the JSON string to be parsed.
     ///
-    /// Returns a `Result` with the `JSValue` containing the parsed value, or an error
-    /// if the input is invalid.
+    /// Returns a `Result` with the `JSValue` containing the parsed value, or
+    /// `Error::InvalidJson` if the input is invalid.
     ///
     /// ```
     /// # use javascriptcore::*;
@@ -139,10 +142,10 @@ impl JSValue {
     /// ```
     ///
     /// [`JSString`]: struct.JSString.html
-    pub fn new_from_json<S: Into<JSString>>(ctx: &JSContext, string: S) -> Result<Self, ()> {
+    pub fn new_from_json<S: Into<JSString>>(ctx: &JSContext, string: S) -> Result<Self, Error> {
         let v = unsafe { ul_sys::JSValueMakeFromJSONString(ctx.raw, string.into().raw) };
         if v.is_null() {
-            Err(())
+            Err(Error::InvalidJson)
         } else {
             Ok(JSValue {
                 raw: v,
@@ -306,6 +309,33 @@ impl JSValue {
         unsafe { ul_sys::JSValueIsObjectOfClass(self.ctx, self.raw, js_class.raw) }
     }
 
+    /// Gets the type of typed array of a JavaScript value.
+    ///
+    /// Returns `kJSTypedArrayTypeNone` if the value is neither a typed
+    /// array nor an `ArrayBuffer`.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    ///
+    /// let v = JSValue::new_number(&ctx, 1.0);
+    /// assert_eq!(v.get_typed_array_type().unwrap(), ul_sys::JSTypedArrayType_kJSTypedArrayTypeNone);
+    /// ```
+    pub fn get_typed_array_type(&self) -> Result<JSTypedArrayType, JSException> {
+        let mut e: ul_sys::JSValueRef = ptr::null_mut();
+        let t = unsafe { ul_sys::JSValueGetTypedArrayType(self.ctx, self.raw, &mut e) };
+        if e.is_null() {
+            Ok(t)
+        } else {
+            Err(JSException {
+                value: JSValue {
+                    raw: e,
+                    ctx: self.ctx,
+                },
+            })
+        }
+    }
+
     /// Tests whether a JavaScript value is an `array`.
     ///
     /// Returns `true` if `value` is an `array`, otherwise `false`.
@@ -405,6 +435,28 @@ impl JSValue {
         }
     }
 
+    /// Calls the method `name` of a JavaScript value, with the value as
+    /// `this`.
+    ///
+    /// Returns either the method's return value, or the exception that was
+    /// thrown, eg. because the value has no such method.
+    ///
+    /// ```
+    /// # use javascriptcore::*;
+    /// let ctx = JSContext::default();
+    ///
+    /// let v = JSValue::new_from_json(&ctx, "[1, 2, 3]").expect("valid array");
+    /// let s = v.call_method("join", &[JSValue::new_string(&ctx, "-")]).expect("call");
+    /// assert_eq!(s.as_string().expect("string"), "1-2-3");
+    /// ```
+    pub fn call_method<S>(&self, name: S, args: &[JSValue]) -> Result<JSValue, JSException>
+        where
+            S: Into<JSString>,
+    {
+        let this = self.as_object()?;
+        this.get_property(name)?.as_object()?.call(Some(&this), args)
+    }
+
     /// Converts a JavaScript value to object and returns the resulting object.
     ///
     /// Returns either the `JSObject` result of conversion, or an exception
@@ -613,4 +665,15 @@ mod tests {
         let v = JSValue::new_from_json(&ctx, "3 +");
         assert!(v.is_err());
     }
+
+    #[test]
+    fn call_method() {
+        let ctx = JSContext::default();
+
+        let v = JSValue::new_from_json(&ctx, "{\"items\": [1, 2]}").expect("value");
+        let items = v.as_object().unwrap().get_property("items").unwrap();
+        let n = items.call_method("push", &[JSValue::new_number(&ctx, 3.0)]).unwrap();
+        assert_eq!(n.as_number().unwrap(), 3.0);
+        assert!(items.call_method("nope", &[]).is_err());
+    }
 }
@@ -0,0 +1,221 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// Modifier keys held during a key event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct Modifiers(u32);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    pub const ALT: Modifiers = Modifiers(1 << 0);
+    pub const CTRL: Modifiers = Modifiers(1 << 1);
+    pub const META: Modifiers = Modifiers(1 << 2);
+    pub const SHIFT: Modifiers = Modifiers(1 << 3);
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, other: Modifiers) {
+        self.0 |= other.0;
+    }
+}
+
+/// Physical keys, by the (Windows) virtual key codes Ultralight expects.
+///
+/// Ultralight derives the DOM key identifier from the virtual key code, so
+/// these are the same on every platform. Punctuation keys are named after
+/// their position on a US keyboard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Key {
+    Backspace = 0x08,
+    Tab = 0x09,
+    Enter = 0x0D,
+    Shift = 0x10,
+    Control = 0x11,
+    Alt = 0x12,
+    Pause = 0x13,
+    CapsLock = 0x14,
+    Escape = 0x1B,
+    Space = 0x20,
+    PageUp = 0x21,
+    PageDown = 0x22,
+    End = 0x23,
+    Home = 0x24,
+    Left = 0x25,
+    Up = 0x26,
+    Right = 0x27,
+    Down = 0x28,
+    Insert = 0x2D,
+    Delete = 0x2E,
+    Digit0 = 0x30,
+    Digit1 = 0x31,
+    Digit2 = 0x32,
+    Digit3 = 0x33,
+    Digit4 = 0x34,
+    Digit5 = 0x35,
+    Digit6 = 0x36,
+    Digit7 = 0x37,
+    Digit8 = 0x38,
+    Digit9 = 0x39,
+    A = 0x41,
+    B = 0x42,
+    C = 0x43,
+    D = 0x44,
+    E = 0x45,
+    F = 0x46,
+    G = 0x47,
+    H = 0x48,
+    I = 0x49,
+    J = 0x4A,
+    K = 0x4B,
+    L = 0x4C,
+    M = 0x4D,
+    N = 0x4E,
+    O = 0x4F,
+    P = 0x50,
+    Q = 0x51,
+    R = 0x52,
+    S = 0x53,
+    T = 0x54,
+    U = 0x55,
+    V = 0x56,
+    W = 0x57,
+    X = 0x58,
+    Y = 0x59,
+    Z = 0x5A,
+    Meta = 0x5B,
+    F1 = 0x70,
+    F2 = 0x71,
+    F3 = 0x72,
+    F4 = 0x73,
+    F5 = 0x74,
+    F6 = 0x75,
+    F7 = 0x76,
+    F8 = 0x77,
+    F9 = 0x78,
+    F10 = 0x79,
+    F11 = 0x7A,
+    F12 = 0x7B,
+    Semicolon = 0xBA,
+    Equal = 0xBB,
+    Comma = 0xBC,
+    Minus = 0xBD,
+    Period = 0xBE,
+    Slash = 0xBF,
+    Backquote = 0xC0,
+    BracketLeft = 0xDB,
+    Backslash = 0xDC,
+    BracketRight = 0xDD,
+    Quote = 0xDE,
+}
+
+const LETTERS: [Key; 26] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+    Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R,
+    Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+];
+
+const DIGITS: [Key; 10] = [
+    Key::Digit0, Key::Digit1, Key::Digit2, Key::Digit3, Key::Digit4,
+    Key::Digit5, Key::Digit6, Key::Digit7, Key::Digit8, Key::Digit9,
+];
+
+// (key, unshifted character, shifted character) on a US keyboard
+const SYMBOLS: [(Key, char, char); 21] = [
+    (Key::Digit1, '1', '!'),
+    (Key::Digit2, '2', '@'),
+    (Key::Digit3, '3', '#'),
+    (Key::Digit4, '4', '$'),
+    (Key::Digit5, '5', '%'),
+    (Key::Digit6, '6', '^'),
+    (Key::Digit7, '7', '&'),
+    (Key::Digit8, '8', '*'),
+    (Key::Digit9, '9', '('),
+    (Key::Digit0, '0', ')'),
+    (Key::Minus, '-', '_'),
+    (Key::Equal, '=', '+'),
+    (Key::BracketLeft, '[', '{'),
+    (Key::BracketRight, ']', '}'),
+    (Key::Backslash, '\\', '|'),
+    (Key::Semicolon, ';', ':'),
+    (Key::Quote, '\'', '"'),
+    (Key::Comma, ',', '<'),
+    (Key::Period, '.', '>'),
+    (Key::Slash, '/', '?'),
+    (Key::Backquote, '`', '~'),
+];
+
+impl Key {
+    pub fn virtual_key_code(self) -> i32 {
+        self as i32
+    }
+
+    /// Maps a character to the key producing it on a US keyboard, and
+    /// whether shift has to be held.
+    ///
+    /// Returns `None` for characters without a key of their own (eg.
+    /// `é`), those can still be sent as plain `Char` events.
+    pub fn from_char(c: char) -> Option<(Key, bool)> {
+        match c {
+            'a'..='z' => Some((LETTERS[c as usize - 'a' as usize], false)),
+            'A'..='Z' => Some((LETTERS[c as usize - 'A' as usize], true)),
+            '0'..='9' => Some((DIGITS[c as usize - '0' as usize], false)),
+            ' ' => Some((Key::Space, false)),
+            '\t' => Some((Key::Tab, false)),
+            '\n' | '\r' => Some((Key::Enter, false)),
+            '\u{8}' => Some((Key::Backspace, false)),
+            '\u{1b}' => Some((Key::Escape, false)),
+            _ => SYMBOLS.iter().find_map(|&(key, unshifted, shifted)| {
+                if c == unshifted {
+                    Some((key, false))
+                } else if c == shifted {
+                    Some((key, true))
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+
+    /// The text a key press inserts, if any, with or without shift held.
+    pub fn text(self, shift: bool) -> Option<char> {
+        let code = self as u8;
+
+        match self {
+            Key::A | Key::B | Key::C | Key::D | Key::E | Key::F | Key::G | Key::H
+            | Key::I | Key::J | Key::K | Key::L | Key::M | Key::N | Key::O | Key::P
+            | Key::Q | Key::R | Key::S | Key::T | Key::U | Key::V | Key::W | Key::X
+            | Key::Y | Key::Z => {
+                if shift {
+                    Some(code as char)
+                } else {
+                    Some(code.to_ascii_lowercase() as char)
+                }
+            }
+            Key::Space => Some(' '),
+            Key::Tab => Some('\t'),
+            Key::Enter => Some('\r'),
+            _ => SYMBOLS.iter().find_map(|&(key, unshifted, shifted)| {
+                if key == self {
+                    Some(if shift { shifted } else { unshifted })
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+}
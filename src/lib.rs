@@ -16,38 +16,68 @@ pub mod ul_config;
 pub mod ul_view_config;
 pub mod settings;
 
+pub mod error;
 pub mod helpers;
-
-use helpers::{create_js_function, evaluate_script, set_js_object_property};
+pub mod platform;
 
 mod helpers_internal;
 use helpers_internal::{
     unpack_window_resize_cb,
     unpack_window_close_cb,
-    log_forward_cb,
-    unpack_closure_view_cb,
 };
 
+mod bitmap;
+mod capture;
+mod console;
 mod cursor;
+mod event;
+mod key;
+mod renderer;
+mod view;
+mod view_events;
+mod wait;
 
 pub mod jsc;
 
+use std::any::Any;
 use std::marker::PhantomData;
-use std::os::raw::c_void;
 
 pub type App = ul_sys::ULApp;
+pub type Bitmap<'a> = bitmap::Bitmap<'a>;
+pub type BitmapFormat = bitmap::BitmapFormat;
+pub type CaptureMode = capture::CaptureMode;
 pub type Config = ul_config::UltralightConfig;
+pub type ConsoleMessage = console::ConsoleMessage;
 pub type Settings = settings::UltralightSettings;
 pub type Monitor = ul_sys::ULMonitor;
 pub type Overlay = ul_sys::ULOverlay;
-pub type Renderer = ul_sys::ULRenderer;
-pub type View = ul_sys::ULView;
+pub type Renderer = renderer::Renderer;
+pub type ScrollEvent = event::ScrollEvent;
+pub type ScrollEventType = event::ScrollEventType;
+pub type View<'r> = view::View<'r>;
+pub type ViewConfig = ul_view_config::UltralightViewConfig;
+pub type WaitOptions = wait::WaitOptions;
 pub type Window = ul_sys::ULWindow;
 
+pub type ChildViewRequest = view_events::ChildViewRequest;
 pub type Cursor = cursor::Cursor;
-
-#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
-pub struct NoneError {}
+pub type FullPageCapture = capture::FullPageCapture;
+pub type IntRect = view_events::IntRect;
+pub type Key = key::Key;
+pub type KeyEvent = event::KeyEvent;
+pub type KeyEventType = event::KeyEventType;
+pub type LoadError = view_events::LoadError;
+pub type LoadEvent = view_events::LoadEvent;
+pub type MessageLevel = console::MessageLevel;
+pub type MessageSource = console::MessageSource;
+pub type Modifiers = key::Modifiers;
+pub type MouseButton = event::MouseButton;
+pub type MouseEvent = event::MouseEvent;
+pub type MouseEventType = event::MouseEventType;
+
+pub use error::Error;
+
+use jsc::{JSException, JSString, JSValue, Protected};
 
 /*
     Current flow
@@ -104,6 +134,39 @@ pub struct NoneError {}
     let renderer = ul_sys::ulAppGetRenderer(app); -> renderer
 */
 
+/// The overlay of an `UltralightApp`'s window, passed to its resize
+/// callback.
+pub struct AppOverlay {
+    raw: Overlay,
+}
+
+impl AppOverlay {
+    /// Returns the underlying `ULOverlay`.
+    pub fn raw(&self) -> Overlay {
+        self.raw
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { ul_sys::ulOverlayGetWidth(self.raw) }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { ul_sys::ulOverlayGetHeight(self.raw) }
+    }
+
+    pub fn move_to(&self, x: i32, y: i32) {
+        unsafe {
+            ul_sys::ulOverlayMoveTo(self.raw, x, y);
+        }
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        unsafe {
+            ul_sys::ulOverlayResize(self.raw, width, height);
+        }
+    }
+}
+
 pub struct UltralightApp<'a> {
     config: Config,
     settings: Settings,
@@ -116,6 +179,10 @@ pub struct UltralightApp<'a> {
     overlay: Option<Overlay>,
     window: Option<Window>,
 
+    // owned window callbacks, the native side only holds pointers to them
+    close_callback: Option<Box<dyn Any>>,
+    resize_callback: Option<Box<dyn Any>>,
+
     phantom: PhantomData<&'a ()>,
 }
 
@@ -146,6 +213,8 @@ impl<'a> UltralightApp<'a> {
                 monitor,
                 window: None,
                 overlay: None,
+                close_callback: None,
+                resize_callback: None,
                 phantom: PhantomData,
             }
         }
@@ -192,8 +261,14 @@ impl<'a> UltralightApp<'a> {
         self.overlay = Some(overlay);
     }
 
+    /// Returns the raw overlay created by `window`.
+    pub fn overlay(&self) -> Option<Overlay> {
+        self.overlay
+    }
+
     pub fn get_renderer(&mut self) -> Renderer {
-        unsafe { ul_sys::ulAppGetRenderer(self.app) }
+        // the app owns its renderer, so we only borrow it
+        unsafe { Renderer::from_raw(ul_sys::ulAppGetRenderer(self.app), false) }
     }
 
     pub fn run(&mut self) {
@@ -203,78 +278,97 @@ impl<'a> UltralightApp<'a> {
     }
 }
 
+impl<'a> Drop for UltralightApp<'a> {
+    fn drop(&mut self) {
+        // the callbacks are freed along with the app, so the window must
+        // not call them anymore
+        if let Some(window) = self.window {
+            unsafe {
+                ul_sys::ulWindowSetCloseCallback(window, None, std::ptr::null_mut());
+                ul_sys::ulWindowSetResizeCallback(window, None, std::ptr::null_mut());
+            }
+        }
+
+        // the overlay's view is borrowed by `Ultralight`, whose handle leaves
+        // the callbacks registered on it
+        if let Some(overlay) = self.overlay {
+            view::release_callbacks(unsafe { ul_sys::ulOverlayGetView(overlay) });
+        }
+    }
+}
+
 pub trait UltralightAppOverlay {
-    fn overlay_get_view(&mut self) -> Result<View, NoneError>;
+    fn overlay_get_view(&mut self) -> Result<ul_sys::ULView, Error>;
 
-    fn overlay_get_height(&mut self) -> Result<u32, NoneError>;
-    fn overlay_get_width(&mut self) -> Result<u32, NoneError>;
+    fn overlay_get_height(&mut self) -> Result<u32, Error>;
+    fn overlay_get_width(&mut self) -> Result<u32, Error>;
 
-    fn overlay_get_x(&mut self) -> Result<i32, NoneError>;
-    fn overlay_get_y(&mut self) -> Result<i32, NoneError>;
+    fn overlay_get_x(&mut self) -> Result<i32, Error>;
+    fn overlay_get_y(&mut self) -> Result<i32, Error>;
 
-    fn overlay_focus(&mut self) -> Result<(), NoneError>;
-    fn overlay_unfocus(&mut self) -> Result<(), NoneError>;
-    fn overlay_has_focus(&mut self) -> Result<bool, NoneError>;
+    fn overlay_focus(&mut self) -> Result<(), Error>;
+    fn overlay_unfocus(&mut self) -> Result<(), Error>;
+    fn overlay_has_focus(&mut self) -> Result<bool, Error>;
 
-    fn overlay_hide(&mut self) -> Result<(), NoneError>;
-    fn overlay_is_hidden(&mut self) -> Result<bool, NoneError>;
+    fn overlay_hide(&mut self) -> Result<(), Error>;
+    fn overlay_is_hidden(&mut self) -> Result<bool, Error>;
 
-    fn overlay_move_to(&self, x: i32, y: i32) -> Result<(), NoneError>;
-    fn overlay_resize(&self, width: u32, height: u32) -> Result<(), NoneError>;
+    fn overlay_move_to(&self, x: i32, y: i32) -> Result<(), Error>;
+    fn overlay_resize(&self, width: u32, height: u32) -> Result<(), Error>;
 }
 
 impl<'a> UltralightAppOverlay for UltralightApp<'a> {
-    fn overlay_get_view(&mut self) -> Result<View, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayGetView(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_get_view(&mut self) -> Result<ul_sys::ULView, Error> {
+        unsafe { Ok(ul_sys::ulOverlayGetView(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_get_height(&mut self) -> Result<u32, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayGetHeight(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_get_height(&mut self) -> Result<u32, Error> {
+        unsafe { Ok(ul_sys::ulOverlayGetHeight(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_get_width(&mut self) -> Result<u32, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayGetWidth(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_get_width(&mut self) -> Result<u32, Error> {
+        unsafe { Ok(ul_sys::ulOverlayGetWidth(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_get_x(&mut self) -> Result<i32, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayGetX(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_get_x(&mut self) -> Result<i32, Error> {
+        unsafe { Ok(ul_sys::ulOverlayGetX(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_get_y(&mut self) -> Result<i32, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayGetY(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_get_y(&mut self) -> Result<i32, Error> {
+        unsafe { Ok(ul_sys::ulOverlayGetY(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_focus(&mut self) -> Result<(), NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayFocus(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_focus(&mut self) -> Result<(), Error> {
+        unsafe { Ok(ul_sys::ulOverlayFocus(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_unfocus(&mut self) -> Result<(), NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayUnfocus(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_unfocus(&mut self) -> Result<(), Error> {
+        unsafe { Ok(ul_sys::ulOverlayUnfocus(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_has_focus(&mut self) -> Result<bool, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayHasFocus(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_has_focus(&mut self) -> Result<bool, Error> {
+        unsafe { Ok(ul_sys::ulOverlayHasFocus(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_hide(&mut self) -> Result<(), NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayHide(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_hide(&mut self) -> Result<(), Error> {
+        unsafe { Ok(ul_sys::ulOverlayHide(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_is_hidden(&mut self) -> Result<bool, NoneError> {
-        unsafe { Ok(ul_sys::ulOverlayIsHidden(self.overlay.ok_or(NoneError {})?)) }
+    fn overlay_is_hidden(&mut self) -> Result<bool, Error> {
+        unsafe { Ok(ul_sys::ulOverlayIsHidden(self.overlay.ok_or(Error::MissingOverlay)?)) }
     }
 
-    fn overlay_move_to(&self, x: i32, y: i32) -> Result<(), NoneError> {
+    fn overlay_move_to(&self, x: i32, y: i32) -> Result<(), Error> {
         unsafe {
-            ul_sys::ulOverlayMoveTo(self.overlay.ok_or(NoneError {})?, x, y);
+            ul_sys::ulOverlayMoveTo(self.overlay.ok_or(Error::MissingOverlay)?, x, y);
         }
 
         Ok(())
     }
 
-    fn overlay_resize(&self, width: u32, height: u32) -> Result<(), NoneError> {
+    fn overlay_resize(&self, width: u32, height: u32) -> Result<(), Error> {
         unsafe {
-            ul_sys::ulOverlayResize(self.overlay.ok_or(NoneError {})?, width, height);
+            ul_sys::ulOverlayResize(self.overlay.ok_or(Error::MissingOverlay)?, width, height);
         }
 
         Ok(())
@@ -282,107 +376,140 @@ impl<'a> UltralightAppOverlay for UltralightApp<'a> {
 }
 
 pub trait UltralightAppWindow {
-    fn window_close(&self) -> Result<(), NoneError>;
+    fn window_close(&self) -> Result<(), Error>;
 
-    fn window_device_to_pixel(&self, val: i32) -> Result<i32, NoneError>;
-    fn window_pixels_to_device(&self, val: i32) -> Result<i32, NoneError>;
+    fn window_device_to_pixel(&self, val: i32) -> Result<i32, Error>;
+    fn window_pixels_to_device(&self, val: i32) -> Result<i32, Error>;
 
-    fn window_get_height(&self) -> Result<u32, NoneError>;
-    fn window_get_width(&self) -> Result<u32, NoneError>;
-    fn window_get_scale(&self) -> Result<f64, NoneError>;
+    fn window_get_height(&self) -> Result<u32, Error>;
+    fn window_get_width(&self) -> Result<u32, Error>;
+    fn window_get_scale(&self) -> Result<f64, Error>;
 
-    fn window_set_title(&mut self, title: &str) -> Result<(), NoneError>;
-    fn window_set_cursor(&mut self, cursor: Cursor) -> Result<(), NoneError>;
+    fn window_set_title(&mut self, title: &str) -> Result<(), Error>;
+    fn window_set_cursor(&mut self, cursor: Cursor) -> Result<(), Error>;
 }
 
 impl<'a> UltralightAppWindow for UltralightApp<'a> {
-    fn window_close(&self) -> Result<(), NoneError> {
-        unsafe { Ok(ul_sys::ulWindowClose(self.window.ok_or(NoneError {})?)) }
+    fn window_close(&self) -> Result<(), Error> {
+        unsafe { Ok(ul_sys::ulWindowClose(self.window.ok_or(Error::MissingWindow)?)) }
     }
 
-    fn window_device_to_pixel(&self, val: i32) -> Result<i32, NoneError> {
-        unsafe { Ok(ul_sys::ulWindowScreenToPixels(self.window.ok_or(NoneError {})?, val)) }
+    fn window_device_to_pixel(&self, val: i32) -> Result<i32, Error> {
+        unsafe { Ok(ul_sys::ulWindowScreenToPixels(self.window.ok_or(Error::MissingWindow)?, val)) }
     }
 
-    fn window_pixels_to_device(&self, val: i32) -> Result<i32, NoneError> {
-        unsafe { Ok(ul_sys::ulWindowPixelsToScreen(self.window.ok_or(NoneError {})?, val)) }
+    fn window_pixels_to_device(&self, val: i32) -> Result<i32, Error> {
+        unsafe { Ok(ul_sys::ulWindowPixelsToScreen(self.window.ok_or(Error::MissingWindow)?, val)) }
     }
 
-    fn window_get_height(&self) -> Result<u32, NoneError> {
-        unsafe { Ok(ul_sys::ulWindowGetHeight(self.window.ok_or(NoneError {})?)) }
+    fn window_get_height(&self) -> Result<u32, Error> {
+        unsafe { Ok(ul_sys::ulWindowGetHeight(self.window.ok_or(Error::MissingWindow)?)) }
     }
 
-    fn window_get_width(&self) -> Result<u32, NoneError> {
-        unsafe { Ok(ul_sys::ulWindowGetWidth(self.window.ok_or(NoneError {})?)) }
+    fn window_get_width(&self) -> Result<u32, Error> {
+        unsafe { Ok(ul_sys::ulWindowGetWidth(self.window.ok_or(Error::MissingWindow)?)) }
     }
 
-    fn window_get_scale(&self) -> Result<f64, NoneError> {
-        unsafe { Ok(ul_sys::ulWindowGetScale(self.window.ok_or(NoneError {})?)) }
+    fn window_get_scale(&self) -> Result<f64, Error> {
+        unsafe { Ok(ul_sys::ulWindowGetScale(self.window.ok_or(Error::MissingWindow)?)) }
     }
 
-    fn window_set_title(&mut self, title: &str) -> Result<(), NoneError> {
+    fn window_set_title(&mut self, title: &str) -> Result<(), Error> {
         unsafe {
             ul_sys::ulWindowSetTitle(
-                self.window.ok_or(NoneError {})?,
-                std::ffi::CString::new(title).unwrap().as_ptr(),
+                self.window.ok_or(Error::MissingWindow)?,
+                std::ffi::CString::new(title)?.as_ptr(),
             );
         }
 
         Ok(())
     }
 
-    fn window_set_cursor(&mut self, cursor: Cursor) -> Result<(), NoneError> {
+    fn window_set_cursor(&mut self, cursor: Cursor) -> Result<(), Error> {
         unsafe {
             Ok(ul_sys::ulWindowSetCursor(
-                self.window.ok_or(NoneError {})?,
+                self.window.ok_or(Error::MissingWindow)?,
                 cursor as u32
             ))
         }
     }
 }
 
-pub trait UltralightAppWindowCallbacks<'a> {
-    fn window_set_close_callback<T>(&self, cb: &'a mut T) -> Result<(), NoneError>
-        where T: FnMut();
+pub trait UltralightAppWindowCallbacks {
+    fn window_set_close_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where T: FnMut() + 'static;
 
-    fn window_set_resize_callback<T>(&self, cb: &'a mut T) -> Result<(), NoneError>
-        where T: FnMut(u32, u32);
+    /// Called with the window's overlay and its new size when the window
+    /// is resized.
+    fn window_set_resize_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where T: FnMut(&AppOverlay, u32, u32) + 'static;
 }
 
-impl<'a> UltralightAppWindowCallbacks<'a> for UltralightApp<'a> {
-    fn window_set_close_callback<T>(&self, cb: &'a mut T) -> Result<(), NoneError>
-        where T: FnMut(),
+impl<'a> UltralightAppWindowCallbacks for UltralightApp<'a> {
+    fn window_set_close_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where T: FnMut() + 'static,
     {
+        let window = self.window.ok_or(Error::MissingWindow)?;
+        let mut cb = Box::new(cb);
+
         unsafe {
-            let (cb_closure, cb_function) = unpack_window_close_cb(cb);
+            let (cb_closure, cb_function) = unpack_window_close_cb(&mut *cb);
 
-            Ok(ul_sys::ulWindowSetCloseCallback(
-                self.window.ok_or(NoneError {})?,
-                Some(cb_function),
-                cb_closure,
-            ))
+            ul_sys::ulWindowSetCloseCallback(window, Some(cb_function), cb_closure);
         }
+
+        // frees the previous callback, which isn't referenced anymore
+        self.close_callback = Some(cb as Box<dyn Any>);
+
+        Ok(())
     }
 
-    fn window_set_resize_callback<T>(&self, cb: &'a mut T) -> Result<(), NoneError>
-        where T: FnMut(u32, u32),
+    fn window_set_resize_callback<T>(&mut self, mut cb: T) -> Result<(), Error>
+        where T: FnMut(&AppOverlay, u32, u32) + 'static,
     {
+        let window = self.window.ok_or(Error::MissingWindow)?;
+        let overlay = AppOverlay {
+            raw: self.overlay.ok_or(Error::MissingOverlay)?,
+        };
+
+        let mut cb = Box::new(move |width, height| cb(&overlay, width, height));
+
         unsafe {
-            let (cb_closure, cb_function) = unpack_window_resize_cb(cb);
+            let (cb_closure, cb_function) = unpack_window_resize_cb(&mut *cb);
 
-            Ok(ul_sys::ulWindowSetResizeCallback(
-                self.window.ok_or(NoneError {})?,
-                Some(cb_function),
-                cb_closure,
-            ))
+            ul_sys::ulWindowSetResizeCallback(window, Some(cb_function), cb_closure);
+        }
+
+        self.resize_callback = Some(cb as Box<dyn Any>);
+
+        Ok(())
+    }
+}
+
+// The view shown by `Ultralight`, only ever lent out through handles
+// borrowing it, so none can outlive the renderer.
+enum UltralightView<'a> {
+    // created by `Ultralight::view`, destroyed along with it
+    Owned(ul_sys::ULView),
+    // passed to `Ultralight::set_view`
+    Set(View<'a>),
+    // the view of an app's overlay, owned by the app
+    Borrowed(ul_sys::ULView),
+}
+
+impl<'a> UltralightView<'a> {
+    fn raw(&self) -> ul_sys::ULView {
+        match self {
+            UltralightView::Owned(raw) | UltralightView::Borrowed(raw) => *raw,
+            UltralightView::Set(view) => view.raw(),
         }
     }
 }
 
 pub struct Ultralight<'a> {
+    view: Option<UltralightView<'a>>,
     config: Config,
     renderer: Renderer,
-    view: Option<View>,
 
     phantom: PhantomData<&'a ()>,
 }
@@ -396,140 +523,359 @@ impl<'a> Ultralight<'a> {
 
         let used_renderer = match renderer {
             Some(renderer) => renderer,
-            None => unsafe { ul_sys::ulCreateRenderer(ulconfig.to_ulconfig()) },
+            None => Renderer::new(&ulconfig),
         };
 
         Ultralight {
+            view: None,
             config: ulconfig,
             renderer: used_renderer,
-            view: None,
 
             phantom: PhantomData,
         }
     }
 
-    pub fn app(&mut self, app: &mut UltralightApp) -> Result<(), NoneError> {
-        self.view = Some(app.overlay_get_view()?);
+    pub fn app(&mut self, app: &mut UltralightApp) -> Result<(), Error> {
+        // the overlay owns its view, so we only borrow it
+        let raw = app.overlay_get_view()?;
+        self.replace_view(UltralightView::Borrowed(raw));
 
         Ok(())
     }
 
-    pub fn set_view(&mut self, view: View) {
-        self.view = Some(view);
+    pub fn set_view(&mut self, view: View<'a>) {
+        self.replace_view(UltralightView::Set(view));
     }
 
     pub fn view(&mut self, width: u32, height: u32, transparent: bool) {
-        unsafe {
-            self.view = Some(ul_sys::ulCreateView(self.renderer, width, height, transparent));
+        let mut view_config = ViewConfig::new();
+
+        view_config.isTransparent(transparent);
+
+        let raw = unsafe {
+            view::create_raw_view(self.renderer.raw(), width, height, Some(view_config))
+        };
+
+        // tracked like the views created through the renderer itself
+        self.renderer.register_view(raw);
+
+        // replacing a previously created view destroys it
+        self.replace_view(UltralightView::Owned(raw));
+    }
+
+    fn replace_view(&mut self, view: UltralightView<'a>) {
+        if let Some(previous) = self.view.replace(view) {
+            self.destroy_view(previous);
         }
     }
 
-    pub fn load_url(&mut self, url: &'static str) -> Result<(), NoneError> {
-        unsafe {
-            let url_ulstr = helpers_internal::ul_string(url);
+    // The views created by `view` can't refer to the renderer they are
+    // registered with, as it is owned by `self`, so they are unregistered
+    // here. Other views are dropped, or left to their owner.
+    fn destroy_view(&self, view: UltralightView<'a>) {
+        if let UltralightView::Owned(raw) = view {
+            self.renderer.unregister_view(raw);
 
-            ul_sys::ulViewLoadURL(self.view.ok_or(NoneError {})?, url_ulstr);
+            drop(unsafe { View::from_raw(raw, true) });
         }
+    }
 
-        Ok(())
+    /// Returns a handle to the view, which can't outlive this borrow.
+    pub fn get_view(&mut self) -> Result<View<'_>, Error> {
+        self.lend_view()
     }
 
-    pub fn load_html(&mut self, code: &'static str) -> Result<(), NoneError> {
-        unsafe {
-            let code_ulstr = helpers_internal::ul_string(code);
+    // Only used while `self` is borrowed, so the handle can't be kept
+    // around or used along with another one.
+    fn lend_view(&self) -> Result<View<'_>, Error> {
+        let raw = self.view.as_ref().ok_or(Error::MissingView)?.raw();
 
-            ul_sys::ulViewLoadHTML(self.view.ok_or(NoneError {})?, code_ulstr);
-        }
+        Ok(unsafe { View::from_raw(raw, false) })
+    }
+
+    pub fn load_url<S: AsRef<str>>(&mut self, url: S) -> Result<(), Error> {
+        self.get_view()?.load_url(url)
+    }
+
+    pub fn load_html<S: AsRef<str>>(&mut self, code: S) -> Result<(), Error> {
+        self.get_view()?.load_html(code)
+    }
+
+    pub fn url(&mut self) -> Result<String, Error> {
+        self.get_view()?.url()
+    }
+
+    pub fn title(&mut self) -> Result<String, Error> {
+        self.get_view()?.title()
+    }
+
+    pub fn reload(&mut self) -> Result<(), Error> {
+        self.get_view()?.reload();
 
         Ok(())
     }
 
-    pub fn update(&mut self) {
-        unsafe {
-            ul_sys::ulUpdate(self.renderer);
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.get_view()?.stop();
+
+        Ok(())
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        match self.lend_view() {
+            Ok(view) => view.can_go_back(),
+            Err(_) => false,
         }
     }
 
-    pub fn update_until_loaded(&mut self) -> Result<(), NoneError> {
-        unsafe {
-            while ul_sys::ulViewIsLoading(self.view.ok_or(NoneError {})?) {
-                ul_sys::ulUpdate(self.renderer);
-            }
+    pub fn can_go_forward(&self) -> bool {
+        match self.lend_view() {
+            Ok(view) => view.can_go_forward(),
+            Err(_) => false,
         }
+    }
+
+    pub fn go_back(&mut self) -> Result<(), Error> {
+        self.get_view()?.go_back();
+
+        Ok(())
+    }
+
+    pub fn go_forward(&mut self) -> Result<(), Error> {
+        self.get_view()?.go_forward();
 
         Ok(())
     }
 
+    pub fn go_to_history_offset(&mut self, offset: i32) -> Result<(), Error> {
+        self.get_view()?.go_to_history_offset(offset);
+
+        Ok(())
+    }
+
+    pub fn get_renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub fn update(&mut self) {
+        self.renderer.update();
+    }
+
+    pub fn update_until_loaded(&mut self, options: &WaitOptions) -> Result<(), Error> {
+        let mut view = self.lend_view()?;
+
+        self.renderer.update_until_loaded(&mut view, options)
+    }
+
     pub fn render(&mut self) {
-        unsafe {
-            ul_sys::ulRender(self.renderer);
-        }
+        self.renderer.render();
     }
 
-    pub fn scroll(&mut self, delta_x: i32, delta_y: i32) -> Result<(), NoneError> {
-        unsafe {
-            let scrollEvent = ul_sys::ulCreateScrollEvent(
-                ul_sys::ULScrollEventType_kScrollEventType_ScrollByPixel,
-                delta_x,
-                delta_y,
-            );
+    pub fn wait_until<F>(&mut self, options: &WaitOptions, condition: F) -> Result<(), Error>
+        where
+            F: FnMut(&mut View) -> Result<bool, Error>,
+    {
+        let mut view = self.lend_view()?;
 
-            ul_sys::ulViewFireScrollEvent(self.view.ok_or(NoneError {})?, scrollEvent);
+        self.renderer.wait_until(&mut view, options, condition)
+    }
 
-            ul_sys::ulDestroyScrollEvent(scrollEvent);
+    pub fn wait_for_selector<S: AsRef<str>>(
+        &mut self,
+        selector: S,
+        options: &WaitOptions,
+    ) -> Result<(), Error> {
+        let mut view = self.lend_view()?;
 
-            Ok(())
-        }
+        self.renderer.wait_for_selector(&mut view, selector, options)
     }
 
-    pub fn get_scroll_height(&mut self) -> Result<f64, NoneError> {
-        unsafe {
-            let (jsgctx, _) = helpers::getJSContextFromView(self.view.ok_or(NoneError {})?);
+    pub fn wait_for_js<S: AsRef<str>>(
+        &mut self,
+        expression: S,
+        options: &WaitOptions,
+    ) -> Result<(), Error> {
+        let mut view = self.lend_view()?;
 
-            Ok(ul_sys::JSValueToNumber(
-                jsgctx,
-                self.evaluate_script("document.body.scrollHeight").unwrap(),
-                std::ptr::null_mut(),
-            ))
-        }
+        self.renderer.wait_for_js(&mut view, expression, options)
+    }
+
+    pub fn wait_for_network_idle(&mut self, options: &WaitOptions) -> Result<(), Error> {
+        let mut view = self.lend_view()?;
+
+        self.renderer.wait_for_network_idle(&mut view, options)
+    }
+
+    pub fn scroll(&mut self, delta_x: i32, delta_y: i32) -> Result<(), Error> {
+        self.get_view()?.scroll(delta_x, delta_y);
+
+        Ok(())
     }
 
-    pub fn set_finish_loading_callback<T>(&mut self, cb: &'a mut T) -> Result<(), NoneError>
+    pub fn mouse_move(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        self.get_view()?.mouse_move(x, y);
+
+        Ok(())
+    }
+
+    pub fn mouse_down(&mut self, x: i32, y: i32, button: MouseButton) -> Result<(), Error> {
+        self.get_view()?.mouse_down(x, y, button);
+
+        Ok(())
+    }
+
+    pub fn mouse_up(&mut self, x: i32, y: i32, button: MouseButton) -> Result<(), Error> {
+        self.get_view()?.mouse_up(x, y, button);
+
+        Ok(())
+    }
+
+    pub fn click(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        self.get_view()?.click(x, y);
+
+        Ok(())
+    }
+
+    pub fn press_key(&mut self, key: Key, modifiers: Modifiers) -> Result<(), Error> {
+        self.get_view()?.press_key(key, modifiers);
+
+        Ok(())
+    }
+
+    pub fn type_text<S: AsRef<str>>(&mut self, text: S) -> Result<(), Error> {
+        self.get_view()?.type_text(text);
+
+        Ok(())
+    }
+
+    pub fn get_scroll_height(&mut self) -> Result<f64, Error> {
+        self.get_view()?.get_scroll_height()
+    }
+
+    pub fn scroll_by_page(&mut self, delta_x: i32, delta_y: i32) -> Result<(), Error> {
+        self.get_view()?.scroll_by_page(delta_x, delta_y);
+
+        Ok(())
+    }
+
+    pub fn scroll_to(&mut self, x: f64, y: f64) -> Result<(), Error> {
+        self.get_view()?.scroll_to(x, y)
+    }
+
+    pub fn scroll_to_element<S: AsRef<str>>(&mut self, selector: S) -> Result<(), Error> {
+        self.get_view()?.scroll_to_element(selector)
+    }
+
+    pub fn scroll_position(&mut self) -> Result<(f64, f64), Error> {
+        self.get_view()?.scroll_position()
+    }
+
+    pub fn set_begin_loading_callback<T>(&mut self, cb: T) -> Result<(), Error>
         where
-            T: FnMut(View),
+            T: FnMut(ul_sys::ULView, LoadEvent) + 'static,
     {
-        let view = self.view.ok_or(NoneError {})?;
+        self.get_view()?.set_begin_loading_callback(cb);
 
-        unsafe {
-            let (cb_closure, cb_function) = unpack_closure_view_cb(cb);
+        Ok(())
+    }
 
-            ul_sys::ulViewSetFinishLoadingCallback(view, Some(cb_function), cb_closure);
-        }
+    pub fn set_finish_loading_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        self.get_view()?.set_finish_loading_callback(cb);
 
         Ok(())
     }
 
-    pub fn set_dom_ready_callback<T>(&mut self, cb: &'a mut T) -> Result<(), NoneError>
+    pub fn set_fail_loading_callback<T>(&mut self, cb: T) -> Result<(), Error>
         where
-            T: FnMut(View),
+            T: FnMut(ul_sys::ULView, LoadError) + 'static,
     {
-        let view = self.view.ok_or(NoneError {})?;
+        self.get_view()?.set_fail_loading_callback(cb);
 
-        unsafe {
-            let (cb_closure, cb_function) = unpack_closure_view_cb(cb);
+        Ok(())
+    }
 
-            ul_sys::ulViewSetDOMReadyCallback(view, Some(cb_function), cb_closure);
-        }
+    pub fn set_window_object_ready_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, LoadEvent) + 'static,
+    {
+        self.get_view()?.set_window_object_ready_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_dom_ready_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        self.get_view()?.set_dom_ready_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_change_title_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        self.get_view()?.set_change_title_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_change_url_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        self.get_view()?.set_change_url_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_change_tooltip_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        self.get_view()?.set_change_tooltip_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_change_cursor_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, Cursor) + 'static,
+    {
+        self.get_view()?.set_change_cursor_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn set_update_history_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        self.get_view()?.set_update_history_callback(cb);
 
         Ok(())
     }
 
-    pub fn create_function<T>(
+    pub fn set_create_child_view_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, ChildViewRequest) -> Option<ul_sys::ULView> + 'static,
+    {
+        self.get_view()?.set_create_child_view_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn create_function<N, T>(
         &mut self,
-        name: &'static str,
-        hook: &'a mut T,
-    ) -> Result<ul_sys::JSObjectRef, NoneError>
+        name: N,
+        hook: T,
+    ) -> Result<ul_sys::JSObjectRef, Error>
         where
+            N: AsRef<str>,
             T: FnMut(
                 ul_sys::JSContextRef,
                 ul_sys::JSObjectRef,
@@ -537,81 +883,124 @@ impl<'a> Ultralight<'a> {
                 usize,
                 *const ul_sys::JSValueRef,
                 *mut ul_sys::JSValueRef,
-            ) -> ul_sys::JSValueRef,
+            ) -> ul_sys::JSValueRef + 'static,
     {
-        Ok(create_js_function(
-            self.view.ok_or(NoneError {})?,
-            name,
-            hook,
-        ))
+        self.get_view()?.create_function(name, hook)
     }
 
-    pub fn set_js_object_property(
+    pub fn bind_function<N, F, Args>(&mut self, name: N, function: F) -> Result<(), Error>
+        where
+            N: AsRef<str>,
+            F: jsc::JSFunction<Args> + 'static,
+    {
+        self.get_view()?.bind_function(name, function)
+    }
+
+    pub fn set_js_object_property<N: Into<JSString>>(
         &mut self,
-        name: &'static str,
+        name: N,
         object: ul_sys::JSObjectRef,
-    ) -> Result<(), NoneError> {
-        set_js_object_property(self.view.ok_or(NoneError {})?, name, object);
-
-        Ok(())
+    ) -> Result<(), Error> {
+        self.get_view()?.set_js_object_property(name, object)
     }
 
-    pub fn evaluate_script(&mut self, script: &'static str) -> Result<ul_sys::JSValueRef, NoneError> {
-        Ok(evaluate_script(self.view.ok_or(NoneError {})?, script))
+    /// Evaluates a string of JavaScript in the page's context.
+    ///
+    /// Fails with `Error::MissingView` if there is no view, otherwise
+    /// returns the result of `View::evaluate_script`, so the thrown
+    /// exception can be inspected or turned into an `Error` with a second
+    /// `?`.
+    pub fn evaluate_script<S: Into<JSString>>(
+        &mut self,
+        script: S,
+    ) -> Result<Result<Protected<'_, JSValue>, Protected<'_, JSException>>, Error> {
+        Ok(self.get_view()?.evaluate_script(script))
     }
 
-    pub fn get_raw_pixels(&mut self) -> Result<Vec<u8>, NoneError> {
-        unsafe {
-            let bitmap_obj = ul_sys::ulViewGetBitmap(self.view.ok_or(NoneError {})?);
-
-            let bitmap = ul_sys::ulBitmapLockPixels(bitmap_obj);
-            let bitmap_size = ul_sys::ulBitmapGetSize(bitmap_obj);
+    pub fn evaluate_script_with_source<S, U>(
+        &mut self,
+        script: S,
+        source_url: U,
+        starting_line_number: i32,
+    ) -> Result<Result<Protected<'_, JSValue>, Protected<'_, JSException>>, Error>
+        where
+            S: Into<JSString>,
+            U: Into<JSString>,
+    {
+        Ok(self.get_view()?.evaluate_script_with_source(script, source_url, starting_line_number))
+    }
 
-            let bitmap_raw = std::slice::from_raw_parts_mut(
-                bitmap as *mut u8,
-                bitmap_size as usize,
-            );
+    pub fn get_raw_pixels(&mut self) -> Result<Vec<u8>, Error> {
+        self.get_view()?.get_raw_pixels()
+    }
 
-            ul_sys::ulBitmapUnlockPixels(bitmap_obj);
+    pub fn write_png_to_file<S: AsRef<str>>(&mut self, file_name: S) -> Result<(), Error> {
+        self.get_view()?.write_png_to_file(file_name)
+    }
 
-            Ok(bitmap_raw.to_vec())
-        }
+    #[cfg(feature = "image")]
+    pub fn to_image(&mut self) -> Result<image::RgbaImage, Error> {
+        self.get_view()?.to_image()
     }
 
-    pub fn write_png_to_file(&mut self, file_name: &'static str) -> Result<bool, NoneError> {
-        unsafe {
-            let bitmap_obj = ul_sys::ulViewGetBitmap(self.view.ok_or(NoneError {})?);
+    #[cfg(feature = "image")]
+    pub fn save_image<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
+        self.get_view()?.save_image(path)
+    }
 
-            let bitmap = ul_sys::ulBitmapLockPixels(bitmap_obj);
-            let bitmap_size = ul_sys::ulBitmapGetSize(bitmap_obj);
+    pub fn capture_full_page(
+        &mut self,
+        options: &FullPageCapture,
+    ) -> Result<Bitmap<'static>, Error> {
+        let mut view = self.lend_view()?;
 
-            let bitmap_raw = std::slice::from_raw_parts_mut(
-                bitmap as *mut u8,
-                bitmap_size as usize,
-            );
+        self.renderer.capture_full_page(&mut view, options)
+    }
 
-            let fn_c_str = std::ffi::CString::new(file_name).unwrap();
+    pub fn capture_element<S: AsRef<str>>(
+        &mut self,
+        selector: S,
+    ) -> Result<Bitmap<'static>, Error> {
+        let mut view = self.lend_view()?;
 
-            Ok(ul_sys::ulBitmapWritePNG(bitmap_obj, fn_c_str.as_ptr()))
-        }
+        self.renderer.capture_element(&mut view, selector)
     }
 
     pub fn is_loading(&self) -> bool {
-        match self.view {
-            Some(view) => unsafe { ul_sys::ulViewIsLoading(view) },
-            None => false,
+        match self.lend_view() {
+            Ok(view) => view.is_loading(),
+            Err(_) => false,
         }
     }
 
-    pub fn log_to_stdout(&mut self) -> Result<(), NoneError> {
-        unsafe {
-            ul_sys::ulViewSetAddConsoleMessageCallback(
-                self.view.ok_or(NoneError {})?,
-                Some(log_forward_cb),
-                std::ptr::null_mut() as *mut c_void,
-            );
-        }
+    pub fn set_console_message_callback<T>(&mut self, cb: T) -> Result<(), Error>
+        where
+            T: FnMut(ul_sys::ULView, ConsoleMessage) + 'static,
+    {
+        self.get_view()?.set_console_message_callback(cb);
+
+        Ok(())
+    }
+
+    pub fn log_to_stdout(&mut self) -> Result<(), Error> {
+        self.get_view()?.log_to_stdout();
 
         Ok(())
     }
+
+    #[cfg(feature = "log")]
+    pub fn log_to_log_crate(&mut self) -> Result<(), Error> {
+        self.get_view()?.log_to_log_crate();
+
+        Ok(())
+    }
+}
+
+impl<'a> Drop for Ultralight<'a> {
+    fn drop(&mut self) {
+        // before the renderer is dropped along with the fields
+        if let Some(view) = self.view.take() {
+            self.destroy_view(view);
+        }
+    }
 }
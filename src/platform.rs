@@ -0,0 +1,35 @@
+//! Default platform handlers provided by AppCore.
+//!
+//! These are only needed when creating a `Renderer` directly instead of
+//! going through an `UltralightApp`, and have to be enabled before the
+//! renderer is created.
+
+use crate::helpers_internal::ul_string;
+
+/// Initializes the platform font loader and sets it as the current font loader.
+pub fn enable_font_loader() {
+    unsafe {
+        ul_sys::ulEnablePlatformFontLoader();
+    }
+}
+
+/// Initializes the platform file system (needed for loading `file:///` URLs
+/// and the bundled `resources/`) and sets it as the current file system.
+///
+/// Relative paths are resolved against `base_dir`.
+pub fn enable_file_system<S: AsRef<str>>(base_dir: S) {
+    unsafe {
+        let base_dir = ul_string(base_dir.as_ref());
+
+        ul_sys::ulEnablePlatformFileSystem(base_dir.raw);
+    }
+}
+
+/// Initializes the default logger, which writes the log to `log_path`.
+pub fn enable_default_logger<S: AsRef<str>>(log_path: S) {
+    unsafe {
+        let log_path = ul_string(log_path.as_ref());
+
+        ul_sys::ulEnableDefaultLogger(log_path.raw);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{jsc, platform, Config, View, ViewConfig};
+
+use std::cell::RefCell;
+
+/// Manages the lifetime of all views and coordinates painting,
+/// network requests and event dispatch.
+///
+/// A renderer created through `Renderer::new` is destroyed with
+/// `ulDestroyRenderer` when dropped. Ultralight only supports a
+/// single renderer per process.
+pub struct Renderer {
+    raw: ul_sys::ULRenderer,
+    owned: bool,
+
+    // views created through this renderer that are still alive
+    views: RefCell<Vec<ul_sys::ULView>>,
+}
+
+impl Renderer {
+    pub fn new(config: &Config) -> Renderer {
+        unsafe {
+            let ulconfig = config.to_ulconfig();
+
+            let raw = ul_sys::ulCreateRenderer(ulconfig);
+
+            ul_sys::ulDestroyConfig(ulconfig);
+
+            Renderer::from_raw(raw, true)
+        }
+    }
+
+    /// Creates a renderer that does not depend on AppCore windowing.
+    ///
+    /// This enables the platform font loader and file system, with paths
+    /// resolved against `base_dir` (which should contain the SDK's
+    /// `resources/` directory), before creating the renderer. Views created
+    /// through it are rendered on the CPU into bitmaps, so no display or
+    /// GPU is required.
+    pub fn headless<S: AsRef<str>>(config: &Config, base_dir: S) -> Renderer {
+        platform::enable_font_loader();
+        platform::enable_file_system(base_dir);
+
+        Renderer::new(config)
+    }
+
+    /// Wraps a raw `ULRenderer`.
+    ///
+    /// If `owned` is set, the renderer is destroyed when the wrapper is
+    /// dropped, otherwise the wrapper merely borrows a renderer owned
+    /// elsewhere (eg. by an `UltralightApp`).
+    pub(crate) unsafe fn from_raw(raw: ul_sys::ULRenderer, owned: bool) -> Renderer {
+        Renderer {
+            raw,
+            owned,
+
+            views: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the underlying `ULRenderer`.
+    pub fn raw(&self) -> ul_sys::ULRenderer {
+        self.raw
+    }
+
+    /// Creates a new view of the given size (in pixels) that is owned by
+    /// the caller and destroyed when dropped.
+    pub fn create_view(&self, width: u32, height: u32, config: Option<ViewConfig>) -> View {
+        View::new(self, width, height, config)
+    }
+
+    /// Returns the raw views created through this renderer that are still
+    /// alive.
+    ///
+    /// Each view is owned by a single `View`, so only its id is handed out
+    /// here and it must not be used once that `View` is dropped.
+    pub fn views(&self) -> Vec<ul_sys::ULView> {
+        self.views.borrow().clone()
+    }
+
+    /// Returns whether the given view was created through this renderer
+    /// and is still alive.
+    pub fn contains_view(&self, raw: ul_sys::ULView) -> bool {
+        self.views.borrow().contains(&raw)
+    }
+
+    pub fn view_count(&self) -> usize {
+        self.views.borrow().len()
+    }
+
+    /// Updates timers and dispatches internal callbacks for all views.
+    ///
+    /// Also settles the promises whose `jsc::PromiseSender` sent a value
+    /// since the last update.
+    pub fn update(&self) {
+        jsc::settle_pending_promises();
+
+        unsafe {
+            ul_sys::ulUpdate(self.raw);
+        }
+    }
+
+    /// Renders all views that need painting.
+    pub fn render(&self) {
+        unsafe {
+            ul_sys::ulRender(self.raw);
+        }
+    }
+
+    pub fn purge_memory(&self) {
+        unsafe {
+            ul_sys::ulPurgeMemory(self.raw);
+        }
+    }
+
+    pub(crate) fn register_view(&self, raw: ul_sys::ULView) {
+        self.views.borrow_mut().push(raw);
+    }
+
+    pub(crate) fn unregister_view(&self, raw: ul_sys::ULView) {
+        self.views.borrow_mut().retain(|&view| view != raw);
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if self.owned {
+            // settling them later on would use the destroyed contexts
+            jsc::discard_all_pending_promises();
+
+            unsafe {
+                ul_sys::ulDestroyRenderer(self.raw);
+            }
+        }
+    }
+}
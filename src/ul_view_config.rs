@@ -56,7 +56,7 @@ impl UltralightViewConfig {
         set_config_str!(view_config, self, fontFamilyStandard, ulViewConfigSetFontFamilyStandard);
 
         set_config!(view_config, self, isAccelerated, ulViewConfigSetIsAccelerated);
-        set_config!(view_config, self, isTransparent, ulViewConfigSetIsAccelerated);
+        set_config!(view_config, self, isTransparent, ulViewConfigSetIsTransparent);
 
         set_config_str!(view_config, self, userAgent, ulViewConfigSetUserAgent);
 
@@ -74,5 +74,8 @@ impl UltralightViewConfig {
     config_item!( fontFamilySerif, String, "Set default font-family to use (Default = Times New Roman)." );
     config_item!( fontFamilyStandard, String, "Set default font-family to use (Default = Times New Roman)" );
 
+    config_item!( isAccelerated, bool, "Set whether to render using the GPU renderer (accelerated) or the CPU renderer (unaccelerated)." );
+    config_item!( isTransparent, bool, "Set whether images should be composited with a transparent background (default = false)." );
+
     config_item!( userAgent, String, "Set user agent string (See <Ultralight/platform/Config.h> for the default)." );
 }
@@ -0,0 +1,807 @@
+use crate::helpers::{create_js_function, evaluate_script, set_js_object_property};
+use crate::helpers_internal::{
+    self, unpack_closure_console_cb, unpack_closure_create_child_view_cb,
+    unpack_closure_cursor_cb, unpack_closure_fail_loading_cb, unpack_closure_load_cb,
+    unpack_closure_string_cb, unpack_closure_view_cb, unpack_closure_view_load_cb,
+};
+use crate::jsc::{self, JSContext, JSException, JSFunction, JSString, JSValue, Protected};
+use crate::{
+    Bitmap, ChildViewRequest, ConsoleMessage, Cursor, Error, Key, KeyEvent, KeyEventType,
+    LoadError, LoadEvent, Modifiers, MouseButton, MouseEvent, MouseEventType, Renderer,
+    ScrollEvent, ScrollEventType, ViewConfig,
+};
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    // closures registered as native callbacks, keyed by their view and
+    // callback name, with the function unregistering them again
+    static CALLBACKS: RefCell<HashMap<(ul_sys::ULView, &'static str), Callback>> =
+        RefCell::new(HashMap::new());
+}
+
+type Callback = (Box<dyn Any>, fn(ul_sys::ULView));
+
+/// A web page rendered by a `Renderer`.
+///
+/// Views created through `View::new` or `Renderer::create_view` are
+/// owned and destroyed with `ulDestroyView` when dropped. A view can
+/// not outlive the renderer it was created with.
+///
+/// Callbacks are stored once per view, no matter which handle registered
+/// them, and freed when they are replaced or the owned view is dropped.
+/// Dropping a borrowed handle (eg. the one to an overlay's view) leaves
+/// them registered. JavaScript functions are owned by the page and freed
+/// once garbage collected.
+pub struct View<'r> {
+    raw: ul_sys::ULView,
+    owned: bool,
+
+    renderer: Option<&'r Renderer>,
+}
+
+impl<'r> View<'r> {
+    /// Creates a new view of the given size (in pixels).
+    ///
+    /// Pass `None` as `config` to use the default view configuration.
+    pub fn new(
+        renderer: &'r Renderer,
+        width: u32,
+        height: u32,
+        config: Option<ViewConfig>,
+    ) -> View<'r> {
+        let raw = unsafe { create_raw_view(renderer.raw(), width, height, config) };
+
+        renderer.register_view(raw);
+
+        View {
+            raw,
+            owned: true,
+
+            renderer: Some(renderer),
+        }
+    }
+
+    /// Wraps a raw `ULView`.
+    ///
+    /// If `owned` is set, the view is destroyed when the wrapper is
+    /// dropped, otherwise the wrapper merely borrows a view owned
+    /// elsewhere (eg. by an overlay).
+    pub(crate) unsafe fn from_raw(raw: ul_sys::ULView, owned: bool) -> View<'r> {
+        View {
+            raw,
+            owned,
+
+            renderer: None,
+        }
+    }
+
+    /// Returns the underlying `ULView`.
+    pub fn raw(&self) -> ul_sys::ULView {
+        self.raw
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { ul_sys::ulViewGetWidth(self.raw) }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { ul_sys::ulViewGetHeight(self.raw) }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        unsafe {
+            ul_sys::ulViewResize(self.raw, width, height);
+        }
+    }
+
+    /// Marks the view as dirty, so it is painted on the next
+    /// `Renderer::render` even if nothing changed.
+    pub fn set_needs_paint(&mut self, needs_paint: bool) {
+        unsafe {
+            ul_sys::ulViewSetNeedsPaint(self.raw, needs_paint);
+        }
+    }
+
+    /// Returns the ratio of pixels to CSS pixels the page is rendered with.
+    pub fn device_scale(&mut self) -> Result<f64, Error> {
+        Ok(self.evaluate_script("window.devicePixelRatio")?.as_number()?)
+    }
+
+    pub fn load_url<S: AsRef<str>>(&mut self, url: S) -> Result<(), Error> {
+        unsafe {
+            let url_ulstr = helpers_internal::ul_string(url.as_ref());
+
+            ul_sys::ulViewLoadURL(self.raw, url_ulstr.raw);
+        }
+
+        Ok(())
+    }
+
+    pub fn load_html<S: AsRef<str>>(&mut self, code: S) -> Result<(), Error> {
+        unsafe {
+            let code_ulstr = helpers_internal::ul_string(code.as_ref());
+
+            ul_sys::ulViewLoadHTML(self.raw, code_ulstr.raw);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the URL of the current page.
+    pub fn url(&self) -> Result<String, Error> {
+        unsafe { helpers_internal::from_ul_string(ul_sys::ulViewGetURL(self.raw)) }
+    }
+
+    /// Returns the title of the current page.
+    pub fn title(&self) -> Result<String, Error> {
+        unsafe { helpers_internal::from_ul_string(ul_sys::ulViewGetTitle(self.raw)) }
+    }
+
+    pub fn reload(&mut self) {
+        unsafe {
+            ul_sys::ulViewReload(self.raw);
+        }
+    }
+
+    /// Stops all page loads.
+    pub fn stop(&mut self) {
+        unsafe {
+            ul_sys::ulViewStop(self.raw);
+        }
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        unsafe { ul_sys::ulViewCanGoBack(self.raw) }
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        unsafe { ul_sys::ulViewCanGoForward(self.raw) }
+    }
+
+    pub fn go_back(&mut self) {
+        unsafe {
+            ul_sys::ulViewGoBack(self.raw);
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        unsafe {
+            ul_sys::ulViewGoForward(self.raw);
+        }
+    }
+
+    /// Navigates `offset` entries back (negative) or forward (positive) in
+    /// the history.
+    pub fn go_to_history_offset(&mut self, offset: i32) {
+        unsafe {
+            ul_sys::ulViewGoToHistoryOffset(self.raw, offset);
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        unsafe { ul_sys::ulViewIsLoading(self.raw) }
+    }
+
+    pub fn fire_scroll_event(&mut self, event: &ScrollEvent) {
+        unsafe {
+            ul_sys::ulViewFireScrollEvent(self.raw, event.raw());
+        }
+    }
+
+    /// Scrolls by the given amount of pixels, like a mouse wheel would.
+    pub fn scroll(&mut self, delta_x: i32, delta_y: i32) {
+        self.fire_scroll_event(&ScrollEvent::new(
+            ScrollEventType::ScrollByPixel,
+            delta_x,
+            delta_y,
+        ));
+    }
+
+    /// Scrolls by the given amount of pages.
+    pub fn scroll_by_page(&mut self, delta_x: i32, delta_y: i32) {
+        self.fire_scroll_event(&ScrollEvent::new(
+            ScrollEventType::ScrollByPage,
+            delta_x,
+            delta_y,
+        ));
+    }
+
+    pub fn get_scroll_height(&mut self) -> Result<f64, Error> {
+        Ok(self.evaluate_script("document.body.scrollHeight")?.as_number()?)
+    }
+
+    /// Scrolls the document to the given position, in CSS pixels.
+    pub fn scroll_to(&mut self, x: f64, y: f64) -> Result<(), Error> {
+        self.evaluate_script(format!("window.scrollTo({}, {})", x, y))?;
+
+        Ok(())
+    }
+
+    /// Scrolls the first element matching the CSS `selector` to the top of
+    /// the view.
+    pub fn scroll_to_element<S: AsRef<str>>(&mut self, selector: S) -> Result<(), Error> {
+        let selector = selector.as_ref();
+
+        let found = self
+            .evaluate_script(format!(
+                "(function(e) {{ if (!e) return false; e.scrollIntoView(true); return true; }})\
+                 (document.querySelector({}))",
+                helpers_internal::js_string_literal(selector)
+            ))?
+            .as_boolean();
+
+        if found {
+            Ok(())
+        } else {
+            Err(Error::ElementNotFound(selector.to_string()))
+        }
+    }
+
+    /// Returns the horizontal and vertical scroll offset of the document,
+    /// in CSS pixels.
+    pub fn scroll_position(&mut self) -> Result<(f64, f64), Error> {
+        let x = self.evaluate_script("window.scrollX")?.as_number()?;
+        let y = self.evaluate_script("window.scrollY")?.as_number()?;
+
+        Ok((x, y))
+    }
+
+    pub fn fire_mouse_event(&mut self, event: &MouseEvent) {
+        unsafe {
+            ul_sys::ulViewFireMouseEvent(self.raw, event.raw());
+        }
+    }
+
+    /// Moves the mouse to `(x, y)`, in pixels relative to the view.
+    pub fn mouse_move(&mut self, x: i32, y: i32) {
+        self.fire_mouse_event(&MouseEvent::new(
+            MouseEventType::MouseMoved,
+            x,
+            y,
+            MouseButton::None,
+        ));
+    }
+
+    pub fn mouse_down(&mut self, x: i32, y: i32, button: MouseButton) {
+        self.fire_mouse_event(&MouseEvent::new(MouseEventType::MouseDown, x, y, button));
+    }
+
+    pub fn mouse_up(&mut self, x: i32, y: i32, button: MouseButton) {
+        self.fire_mouse_event(&MouseEvent::new(MouseEventType::MouseUp, x, y, button));
+    }
+
+    /// Moves the mouse to `(x, y)` and clicks the left button there.
+    ///
+    /// The resulting DOM events are dispatched synchronously, but anything
+    /// they schedule (timers, network requests) only runs on the next
+    /// `Renderer::update`.
+    pub fn click(&mut self, x: i32, y: i32) {
+        self.mouse_move(x, y);
+        self.mouse_down(x, y, MouseButton::Left);
+        self.mouse_up(x, y, MouseButton::Left);
+    }
+
+    pub fn fire_key_event(&mut self, event: &KeyEvent) {
+        unsafe {
+            ul_sys::ulViewFireKeyEvent(self.raw, event.raw());
+        }
+    }
+
+    pub fn key_down(&mut self, key: Key, modifiers: Modifiers) {
+        self.fire_key_event(&KeyEvent::key(KeyEventType::RawKeyDown, key, modifiers));
+    }
+
+    pub fn key_up(&mut self, key: Key, modifiers: Modifiers) {
+        self.fire_key_event(&KeyEvent::key(KeyEventType::KeyUp, key, modifiers));
+    }
+
+    /// Presses and releases `key`, inserting its text (if any) in between
+    /// like a physical key press would.
+    pub fn press_key(&mut self, key: Key, modifiers: Modifiers) {
+        self.key_down(key, modifiers);
+
+        // shortcuts like ctrl+a don't insert text
+        let inserts_text = !modifiers.contains(Modifiers::CTRL)
+            && !modifiers.contains(Modifiers::ALT)
+            && !modifiers.contains(Modifiers::META);
+
+        if inserts_text {
+            if let Some(c) = key.text(modifiers.contains(Modifiers::SHIFT)) {
+                let unmodified = key.text(false).unwrap_or(c);
+
+                self.fire_key_event(&KeyEvent::char(
+                    &c.to_string(),
+                    &unmodified.to_string(),
+                    modifiers,
+                ));
+            }
+        }
+
+        self.key_up(key, modifiers);
+    }
+
+    /// Types `text` into the focused element.
+    ///
+    /// Characters with a key on a US keyboard are sent as a key down, char
+    /// and key up sequence (holding shift where needed), others as a lone
+    /// char event.
+    pub fn type_text<S: AsRef<str>>(&mut self, text: S) {
+        for c in text.as_ref().chars() {
+            match Key::from_char(c) {
+                Some((key, shift)) => {
+                    let modifiers = if shift { Modifiers::SHIFT } else { Modifiers::NONE };
+
+                    self.press_key(key, modifiers);
+                }
+                None => {
+                    let c = c.to_string();
+
+                    self.fire_key_event(&KeyEvent::char(&c, &c, Modifiers::NONE));
+                }
+            }
+        }
+    }
+
+    /// Called when a frame starts loading a URL.
+    pub fn set_begin_loading_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, LoadEvent) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_load_cb(&mut *cb);
+
+            ul_sys::ulViewSetBeginLoadingCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("begin_loading", cb, |raw| unsafe {
+            ul_sys::ulViewSetBeginLoadingCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when a frame finished loading a URL.
+    pub fn set_finish_loading_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_view_load_cb(&mut *cb);
+
+            ul_sys::ulViewSetFinishLoadingCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("finish_loading", cb, |raw| unsafe {
+            ul_sys::ulViewSetFinishLoadingCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when a frame failed to load a URL.
+    pub fn set_fail_loading_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, LoadError) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_fail_loading_cb(&mut *cb);
+
+            ul_sys::ulViewSetFailLoadingCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("fail_loading", cb, |raw| unsafe {
+            ul_sys::ulViewSetFailLoadingCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when the JavaScript `window` object of a frame was reset, before
+    /// any scripts of the page run. This is the earliest point to set up
+    /// bindings.
+    pub fn set_window_object_ready_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, LoadEvent) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_load_cb(&mut *cb);
+
+            ul_sys::ulViewSetWindowObjectReadyCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("window_object_ready", cb, |raw| unsafe {
+            ul_sys::ulViewSetWindowObjectReadyCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when the document of the main frame was parsed.
+    pub fn set_dom_ready_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_view_load_cb(&mut *cb);
+
+            ul_sys::ulViewSetDOMReadyCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("dom_ready", cb, |raw| unsafe {
+            ul_sys::ulViewSetDOMReadyCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called with the new title when the page title changes.
+    pub fn set_change_title_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_string_cb(&mut *cb);
+
+            ul_sys::ulViewSetChangeTitleCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("change_title", cb, |raw| unsafe {
+            ul_sys::ulViewSetChangeTitleCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called with the new URL when the page URL changes.
+    pub fn set_change_url_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_string_cb(&mut *cb);
+
+            ul_sys::ulViewSetChangeURLCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("change_url", cb, |raw| unsafe {
+            ul_sys::ulViewSetChangeURLCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called with the new tooltip, eg. when hovering a link.
+    pub fn set_change_tooltip_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, String) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_string_cb(&mut *cb);
+
+            ul_sys::ulViewSetChangeTooltipCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("change_tooltip", cb, |raw| unsafe {
+            ul_sys::ulViewSetChangeTooltipCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when the mouse cursor should change.
+    pub fn set_change_cursor_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, Cursor) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_cursor_cb(&mut *cb);
+
+            ul_sys::ulViewSetChangeCursorCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("change_cursor", cb, |raw| unsafe {
+            ul_sys::ulViewSetChangeCursorCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when the back/forward history changed.
+    pub fn set_update_history_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_view_cb(&mut *cb);
+
+            ul_sys::ulViewSetUpdateHistoryCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("update_history", cb, |raw| unsafe {
+            ul_sys::ulViewSetUpdateHistoryCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Called when the page wants to open a new view.
+    ///
+    /// Return the raw view to load the target URL into (it is kept alive by
+    /// the caller), or `None` to block it.
+    pub fn set_create_child_view_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, ChildViewRequest) -> Option<ul_sys::ULView> + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_create_child_view_cb(&mut *cb);
+
+            ul_sys::ulViewSetCreateChildViewCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("create_child_view", cb, |raw| unsafe {
+            ul_sys::ulViewSetCreateChildViewCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    pub fn create_function<N, T>(
+        &mut self,
+        name: N,
+        hook: T,
+    ) -> Result<ul_sys::JSObjectRef, Error>
+        where
+            N: AsRef<str>,
+            T: FnMut(
+                ul_sys::JSContextRef,
+                ul_sys::JSObjectRef,
+                ul_sys::JSObjectRef,
+                usize,
+                *const ul_sys::JSValueRef,
+                *mut ul_sys::JSValueRef,
+            ) -> ul_sys::JSValueRef + 'static,
+    {
+        create_js_function(self.raw, name, hook)
+    }
+
+    /// Binds `function` to the global `name` of the page, converting its
+    /// arguments and return value with `FromJSValue` and `IntoJSValue`.
+    ///
+    /// Arguments that can't be converted and errors returned by `function`
+    /// are thrown as JavaScript `Error`s.
+    ///
+    /// ```ignore
+    /// view.bind_function("greet", |name: String| format!("Hello, {}!", name))?;
+    /// ```
+    pub fn bind_function<N, F, Args>(&mut self, name: N, mut function: F) -> Result<(), Error>
+        where
+            N: AsRef<str>,
+            F: JSFunction<Args> + 'static,
+    {
+        let object = self.create_function(
+            name.as_ref(),
+            move |ctx, _function, _this, argc, argv, exception| unsafe {
+                let context = JSContext::from_context_ref(ctx);
+
+                let args: Vec<JSValue> = (0..argc)
+                    .map(|i| JSValue {
+                        raw: *argv.add(i),
+                        ctx,
+                    })
+                    .collect();
+
+                match function.call_with(&context, &args) {
+                    Ok(value) => value.raw,
+                    Err(message) => {
+                        let message = JSValue::new_string(&context, message);
+
+                        *exception = ul_sys::JSObjectMakeError(
+                            ctx,
+                            1,
+                            &message.raw,
+                            std::ptr::null_mut(),
+                        );
+
+                        ul_sys::JSValueMakeUndefined(ctx)
+                    }
+                }
+            },
+        )?;
+
+        self.set_js_object_property(name.as_ref(), object)
+    }
+
+    pub fn set_js_object_property<N: Into<JSString>>(
+        &mut self,
+        name: N,
+        object: ul_sys::JSObjectRef,
+    ) -> Result<(), Error> {
+        set_js_object_property(self.raw, name, object)
+    }
+
+    /// Evaluates a string of JavaScript in the page's context.
+    ///
+    /// Returns either the resulting `JSValue` or the exception that
+    /// was thrown, both protected from garbage collection until dropped.
+    /// They can outlive the view, but not its renderer.
+    pub fn evaluate_script<S: Into<JSString>>(
+        &mut self,
+        script: S,
+    ) -> Result<Protected<'r, JSValue>, Protected<'r, JSException>> {
+        evaluate_script(self.raw, script, None, 1)
+    }
+
+    /// Evaluates a string of JavaScript in the page's context, attributing
+    /// it to `source_url` starting at `starting_line_number` when reporting
+    /// exceptions.
+    pub fn evaluate_script_with_source<S, U>(
+        &mut self,
+        script: S,
+        source_url: U,
+        starting_line_number: i32,
+    ) -> Result<Protected<'r, JSValue>, Protected<'r, JSException>>
+        where
+            S: Into<JSString>,
+            U: Into<JSString>,
+    {
+        evaluate_script(self.raw, script, Some(source_url.into()), starting_line_number)
+    }
+
+    /// Returns the bitmap the view is rendered into.
+    ///
+    /// The bitmap is owned by the view's surface and only valid while the
+    /// view is alive and not resized.
+    pub fn bitmap(&self) -> Result<Bitmap<'_>, Error> {
+        unsafe { Ok(Bitmap::from_raw(self.raw_bitmap()?, false)) }
+    }
+
+    pub fn get_raw_pixels(&mut self) -> Result<Vec<u8>, Error> {
+        let bitmap = self.bitmap()?;
+
+        let pixels = bitmap.lock()?;
+
+        Ok(pixels.to_vec())
+    }
+
+    pub fn write_png_to_file<S: AsRef<str>>(&mut self, file_name: S) -> Result<(), Error> {
+        self.bitmap()?.write_png(file_name)
+    }
+
+    /// Converts the view's bitmap into an `image::RgbaImage`.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> Result<image::RgbaImage, Error> {
+        self.bitmap()?.to_image()
+    }
+
+    /// Saves the view's bitmap as PNG, JPEG, WebP or BMP, depending on the
+    /// extension of `path`.
+    #[cfg(feature = "image")]
+    pub fn save_image<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Error> {
+        self.bitmap()?.save(path)
+    }
+
+    /// Called for every message added to the console, eg. through
+    /// `console.log` or because of a JavaScript error.
+    pub fn set_console_message_callback<T>(&mut self, cb: T)
+        where
+            T: FnMut(ul_sys::ULView, ConsoleMessage) + 'static,
+    {
+        let mut cb = Box::new(cb);
+
+        unsafe {
+            let (cb_closure, cb_function) = unpack_closure_console_cb(&mut *cb);
+
+            ul_sys::ulViewSetAddConsoleMessageCallback(self.raw, Some(cb_function), cb_closure);
+        }
+
+        self.store_callback("console_message", cb, |raw| unsafe {
+            ul_sys::ulViewSetAddConsoleMessageCallback(raw, None, std::ptr::null_mut());
+        });
+    }
+
+    /// Prints console messages to stdout, replacing the console message
+    /// callback.
+    pub fn log_to_stdout(&mut self) {
+        self.set_console_message_callback(|_, message| println!("{}", message));
+    }
+
+    /// Logs console messages through the `log` crate with target
+    /// `ul::console`, replacing the console message callback.
+    #[cfg(feature = "log")]
+    pub fn log_to_log_crate(&mut self) {
+        self.set_console_message_callback(|_, message| message.log());
+    }
+
+    // Keeps `closure` alive for as long as the view, replacing (and
+    // freeing) the closure previously registered under `name`.
+    fn store_callback<T: 'static>(
+        &mut self,
+        name: &'static str,
+        closure: Box<T>,
+        unregister: fn(ul_sys::ULView),
+    ) {
+        let previous = CALLBACKS.with(|callbacks| {
+            callbacks
+                .borrow_mut()
+                .insert((self.raw, name), (closure as Box<dyn Any>, unregister))
+        });
+
+        // dropped outside of the borrow, the closure may own another view
+        drop(previous);
+    }
+
+    // The default surface of a CPU rendered view is a bitmap surface,
+    // with the GPU renderer enabled there is no surface at all.
+    unsafe fn raw_bitmap(&self) -> Result<ul_sys::ULBitmap, Error> {
+        let surface = ul_sys::ulViewGetSurface(self.raw);
+
+        if surface.is_null() {
+            return Err(Error::MissingSurface);
+        }
+
+        Ok(ul_sys::ulBitmapSurfaceGetBitmap(surface))
+    }
+}
+
+impl<'r> Drop for View<'r> {
+    fn drop(&mut self) {
+        if self.owned {
+            release_callbacks(self.raw);
+
+            if let Some(renderer) = self.renderer {
+                renderer.unregister_view(self.raw);
+            }
+
+            unsafe {
+                let ctx = JSContext::from_context_ref(ul_sys::ulViewLockJSContext(self.raw));
+                jsc::discard_pending_promises(&ctx);
+                drop(ctx);
+
+                ul_sys::ulViewUnlockJSContext(self.raw);
+
+                ul_sys::ulDestroyView(self.raw);
+            }
+        }
+    }
+}
+
+/// Unregisters and frees all callbacks of the given view.
+pub(crate) fn release_callbacks(raw: ul_sys::ULView) {
+    let released: Vec<Callback> = CALLBACKS.with(|callbacks| {
+        let mut callbacks = callbacks.borrow_mut();
+
+        let keys: Vec<_> = callbacks.keys().filter(|(view, _)| *view == raw).cloned().collect();
+
+        keys.iter().filter_map(|key| callbacks.remove(key)).collect()
+    });
+
+    // Ultralight must not call them anymore once they are freed
+    for (_, unregister) in &released {
+        unregister(raw);
+    }
+}
+
+pub(crate) unsafe fn create_raw_view(
+    renderer: ul_sys::ULRenderer,
+    width: u32,
+    height: u32,
+    config: Option<ViewConfig>,
+) -> ul_sys::ULView {
+    let config = config.unwrap_or_default();
+    let view_config = config.to_ul_viewconfig();
+
+    let view = ul_sys::ulCreateView(
+        renderer,
+        width,
+        height,
+        view_config,
+        std::ptr::null_mut(),
+    );
+
+    ul_sys::ulDestroyViewConfig(view_config);
+
+    view
+}
@@ -0,0 +1,65 @@
+use crate::helpers_internal::from_ul_string;
+
+/// A frame of a view started or finished loading a URL.
+#[derive(Clone, Debug)]
+pub struct LoadEvent {
+    pub frame_id: u64,
+    pub is_main_frame: bool,
+    pub url: String,
+}
+
+/// A frame of a view failed to load a URL.
+#[derive(Clone, Debug)]
+pub struct LoadError {
+    pub frame_id: u64,
+    pub is_main_frame: bool,
+    pub url: String,
+    pub description: String,
+    pub error_domain: String,
+    pub error_code: i32,
+}
+
+/// A rectangle in pixels, given by its edges.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct IntRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+/// A page asked to open a new view, eg. through `target="_blank"` or
+/// `window.open`.
+#[derive(Clone, Debug)]
+pub struct ChildViewRequest {
+    pub opener_url: String,
+    pub target_url: String,
+    pub is_popup: bool,
+    /// Requested position and size of the popup, only set for popups.
+    pub popup_rect: IntRect,
+}
+
+impl LoadEvent {
+    pub(crate) unsafe fn from_raw(
+        frame_id: u64,
+        is_main_frame: bool,
+        url: ul_sys::ULString,
+    ) -> LoadEvent {
+        LoadEvent {
+            frame_id,
+            is_main_frame,
+            url: from_ul_string(url).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ul_sys::ULIntRect> for IntRect {
+    fn from(rect: ul_sys::ULIntRect) -> IntRect {
+        IntRect {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::helpers_internal::js_string_literal;
+use crate::{Error, Renderer, View};
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Options for the `Renderer::wait_*` helpers.
+#[derive(Clone, Debug)]
+pub struct WaitOptions {
+    /// How long to wait before giving up with `Error::Timeout`.
+    pub timeout: Duration,
+    /// How long to sleep between two checks of the condition.
+    pub poll_interval: Duration,
+    /// How long no new requests may be made for the network to count as
+    /// idle in `wait_for_network_idle`.
+    pub network_idle_time: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        WaitOptions {
+            timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(10),
+            network_idle_time: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Renderer {
+    /// Updates and renders until `condition` returns `true` for `view`.
+    ///
+    /// The condition is checked before every update, errors it returns are
+    /// passed on. Fails with `Error::Timeout` if it isn't met within
+    /// `options.timeout`.
+    pub fn wait_until<F>(
+        &self,
+        view: &mut View,
+        options: &WaitOptions,
+        mut condition: F,
+    ) -> Result<(), Error>
+        where
+            F: FnMut(&mut View) -> Result<bool, Error>,
+    {
+        let start = Instant::now();
+
+        loop {
+            if condition(view)? {
+                self.render();
+
+                return Ok(());
+            }
+
+            if start.elapsed() >= options.timeout {
+                return Err(Error::Timeout(options.timeout));
+            }
+
+            self.update();
+            self.render();
+
+            thread::sleep(options.poll_interval);
+        }
+    }
+
+    /// Updates until `view` has finished loading, then renders once so
+    /// its pixels reflect the loaded page.
+    ///
+    /// Fails with `Error::Timeout` if the page is still loading after
+    /// `options.timeout`.
+    pub fn update_until_loaded(&self, view: &mut View, options: &WaitOptions) -> Result<(), Error> {
+        self.wait_until(view, options, |view| Ok(!view.is_loading()))
+    }
+
+    /// Waits until `view` finished loading and an element matches the CSS
+    /// `selector`.
+    pub fn wait_for_selector<S: AsRef<str>>(
+        &self,
+        view: &mut View,
+        selector: S,
+        options: &WaitOptions,
+    ) -> Result<(), Error> {
+        let script = format!(
+            "document.querySelector({}) !== null",
+            js_string_literal(selector.as_ref())
+        );
+
+        self.wait_until(view, options, |view| {
+            Ok(!view.is_loading() && view.evaluate_script(script.as_str())?.as_boolean())
+        })
+    }
+
+    /// Waits until the JavaScript expression `expression` evaluates to a
+    /// truthy value.
+    ///
+    /// Exceptions thrown while evaluating it are treated as falsy, so it
+    /// may refer to globals defined later on by the page.
+    pub fn wait_for_js<S: AsRef<str>>(
+        &self,
+        view: &mut View,
+        expression: S,
+        options: &WaitOptions,
+    ) -> Result<(), Error> {
+        let expression = expression.as_ref();
+
+        self.wait_until(view, options, |view| {
+            Ok(view
+                .evaluate_script(expression)
+                .map(|value| value.as_boolean())
+                .unwrap_or(false))
+        })
+    }
+
+    /// Waits until `view` finished loading and made no new requests for
+    /// `options.network_idle_time`.
+    ///
+    /// Requests are tracked through the resource entries of the Performance
+    /// API.
+    pub fn wait_for_network_idle(
+        &self,
+        view: &mut View,
+        options: &WaitOptions,
+    ) -> Result<(), Error> {
+        let mut requests = None;
+        let mut idle_since = Instant::now();
+
+        self.wait_until(view, options, |view| {
+            if view.is_loading() {
+                requests = None;
+
+                return Ok(false);
+            }
+
+            let count = view
+                .evaluate_script(
+                    "document.readyState === 'complete' ? \
+                     performance.getEntriesByType('resource').length : -1",
+                )?
+                .as_number()?;
+
+            if count < 0.0 || requests != Some(count) {
+                requests = Some(count);
+                idle_since = Instant::now();
+
+                return Ok(false);
+            }
+
+            Ok(idle_since.elapsed() >= options.network_idle_time)
+        })
+    }
+}
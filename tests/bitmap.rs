@@ -0,0 +1,47 @@
+// Bitmaps don't need a renderer, so this doesn't count against the
+// single renderer per process.
+
+use ::ul as ul;
+
+#[test]
+fn converts_premultiplied_bgra_to_rgba() {
+    // two pixels per row, padded to 12 bytes
+    let pixels = [
+        0, 0, 255, 255, 0, 64, 0, 128, 9, 9, 9, 9,
+        0, 0, 0, 0, 255, 255, 255, 255, 9, 9, 9, 9,
+    ];
+
+    let mut bitmap =
+        ul::Bitmap::from_pixels(2, 2, ul::BitmapFormat::Bgra8UnormSrgb, 12, &pixels).unwrap();
+
+    assert_eq!(bitmap.width(), 2);
+    assert_eq!(bitmap.height(), 2);
+    assert_eq!(bitmap.row_bytes(), 12);
+    assert_eq!(bitmap.bytes_per_pixel(), 4);
+    assert_eq!(bitmap.format(), ul::BitmapFormat::Bgra8UnormSrgb);
+
+    assert_eq!(
+        bitmap.to_rgba().unwrap(),
+        vec![
+            255, 0, 0, 255, 0, 128, 0, 128,
+            0, 0, 0, 0, 255, 255, 255, 255,
+        ]
+    );
+
+    {
+        let mut pixels = bitmap.lock_mut().unwrap();
+        pixels[0..4].copy_from_slice(&[255, 0, 0, 255]);
+    }
+
+    // the guard above unlocked the pixels, so they can be locked again
+    assert_eq!(&bitmap.lock().unwrap()[0..4], &[255, 0, 0, 255]);
+    assert_eq!(&bitmap.to_rgba().unwrap()[0..4], &[0, 0, 255, 255]);
+
+    // too short for two rows, or for two pixels per row
+    let format = ul::BitmapFormat::Bgra8UnormSrgb;
+    assert!(ul::Bitmap::from_pixels(2, 2, format, 12, &pixels[..20]).is_err());
+    assert!(ul::Bitmap::from_pixels(2, 2, format, 4, &pixels).is_err());
+
+    let empty = ul::Bitmap::new(0, 0, ul::BitmapFormat::Bgra8UnormSrgb);
+    assert_eq!(empty.to_rgba().unwrap(), Vec::<u8>::new());
+}
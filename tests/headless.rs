@@ -0,0 +1,354 @@
+// Ultralight only supports a single renderer per process, so every
+// integration test file holds exactly one test. Features are checked one
+// after another against that renderer, each with views of its own.
+
+use ::ul as ul;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+fn resources_dir() -> String {
+    std::env::var("UL_RESOURCES_DIR").unwrap_or_else(|_| "./".to_owned())
+}
+
+fn load<'r>(renderer: &'r ul::Renderer, width: u32, height: u32, html: &str) -> ul::View<'r> {
+    let mut view = renderer.create_view(width, height, None);
+
+    view.load_html(html).unwrap();
+    renderer
+        .update_until_loaded(&mut view, &ul::WaitOptions::default())
+        .unwrap();
+
+    view
+}
+
+#[test]
+fn renders_views_without_a_window() {
+    let config = ul::Config::new();
+    let renderer = ul::Renderer::headless(&config, resources_dir());
+
+    renders_and_tracks_views(&renderer);
+    injects_mouse_events(&renderer);
+    injects_key_events(&renderer);
+    captures_full_pages(&renderer);
+    captures_elements(&renderer);
+    navigates_history(&renderer);
+    calls_lifecycle_callbacks(&renderer);
+    reports_console_messages(&renderer);
+    waits_for_conditions(&renderer);
+}
+
+fn renders_and_tracks_views(renderer: &ul::Renderer) {
+    let mut view = renderer.create_view(64, 32, None);
+    assert_eq!(renderer.view_count(), 1);
+    assert_eq!(view.width(), 64);
+    assert_eq!(view.height(), 32);
+
+    view.load_html("<html><body style=\"background: #ff0000\"></body></html>")
+        .unwrap();
+    renderer
+        .update_until_loaded(&mut view, &ul::WaitOptions::default())
+        .unwrap();
+
+    let pixels = view.get_raw_pixels().unwrap();
+    assert!(pixels.len() >= 64 * 32 * 4);
+
+    // BGRA, so red ends up in the third channel
+    assert_eq!(&pixels[0..4], &[0, 0, 255, 255]);
+
+    assert_eq!(
+        view.evaluate_script("1 + 1").unwrap().as_number().unwrap(),
+        2.0
+    );
+    assert!(view.evaluate_script("throw new Error('abc')").is_err());
+
+    {
+        let other = renderer.create_view(16, 16, None);
+        assert_eq!(renderer.view_count(), 2);
+        assert!(renderer.contains_view(other.raw()));
+        assert!(renderer.views().contains(&view.raw()));
+    }
+
+    assert_eq!(renderer.view_count(), 1);
+
+    drop(view);
+    assert_eq!(renderer.view_count(), 0);
+}
+
+fn injects_mouse_events(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        64,
+        "<html><body style=\"margin: 0\">\
+            <div style=\"width: 32px; height: 32px\" onclick=\"window.clicked = true\"></div>\
+            <div style=\"width: 32px; height: 32px\" \
+                onmousedown=\"window.button = event.button\"></div>\
+        </body></html>",
+    );
+
+    view.click(40, 40);
+    assert!(!view.evaluate_script("window.clicked === true").unwrap().as_boolean());
+
+    view.click(16, 16);
+    assert!(view.evaluate_script("window.clicked === true").unwrap().as_boolean());
+
+    view.mouse_move(16, 48);
+    view.mouse_down(16, 48, ul::MouseButton::Right);
+    view.mouse_up(16, 48, ul::MouseButton::Right);
+    assert_eq!(
+        view.evaluate_script("window.button").unwrap().as_number().unwrap(),
+        2.0
+    );
+}
+
+fn injects_key_events(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        64,
+        "<html><body>\
+            <input id=\"input\" onkeydown=\"window.lastKey = event.key\">\
+        </body></html>",
+    );
+
+    view.evaluate_script("document.getElementById('input').focus()").unwrap();
+
+    view.type_text("Hi there!");
+    view.press_key(ul::Key::Backspace, ul::Modifiers::NONE);
+
+    let value = view.evaluate_script("document.getElementById('input').value").unwrap();
+    assert_eq!(value.as_string().unwrap(), "Hi there");
+    assert_eq!(
+        view.evaluate_script("window.lastKey").unwrap().as_string().unwrap(),
+        "Backspace"
+    );
+
+    // shortcuts don't insert text
+    view.press_key(ul::Key::A, ul::Modifiers::CTRL);
+    let value = view.evaluate_script("document.getElementById('input').value").unwrap();
+    assert_eq!(value.as_string().unwrap(), "Hi there");
+}
+
+fn captures_full_pages(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        32,
+        "<html><body style=\"margin: 0\">\
+            <div style=\"height: 100px; background: #ff0000\"></div>\
+            <div style=\"height: 100px; background: #0000ff\"></div>\
+        </body></html>",
+    );
+
+    for &mode in &[ul::CaptureMode::Stitch, ul::CaptureMode::Resize] {
+        let options = ul::FullPageCapture {
+            mode,
+            ..Default::default()
+        };
+        let page = renderer.capture_full_page(&mut view, &options).unwrap();
+
+        assert_eq!((page.width(), page.height()), (64, 200));
+
+        // BGRA, red at the top and blue at the bottom of the document
+        let row_bytes = page.row_bytes() as usize;
+        let pixels = page.lock().unwrap();
+        assert_eq!(&pixels[..4], &[0, 0, 255, 255]);
+        assert_eq!(&pixels[150 * row_bytes..150 * row_bytes + 4], &[255, 0, 0, 255]);
+        drop(pixels);
+
+        // the view is restored afterwards
+        assert_eq!((view.width(), view.height()), (64, 32));
+        assert_eq!(view.scroll_position().unwrap(), (0.0, 0.0));
+    }
+
+    let options = ul::FullPageCapture {
+        max_height: Some(50),
+        ..Default::default()
+    };
+    let page = renderer.capture_full_page(&mut view, &options).unwrap();
+    assert_eq!(page.height(), 50);
+
+    // the body clips the page, so the document itself can't scroll
+    view.evaluate_script(
+        "document.documentElement.style.cssText = 'height: 100%; overflow: hidden';\
+         document.body.style.cssText = 'margin: 0; height: 100%; overflow: hidden';",
+    )
+    .unwrap();
+    let options = ul::FullPageCapture {
+        mode: ul::CaptureMode::Stitch,
+        ..Default::default()
+    };
+    assert!(matches!(
+        renderer.capture_full_page(&mut view, &options),
+        Err(ul::Error::NotScrollable)
+    ));
+}
+
+fn captures_elements(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        32,
+        "<html><body style=\"margin: 0\">\
+            <div id=\"near\" style=\"margin: 4px; width: 20px; height: 10px; \
+                background: #00ff00\"></div>\
+            <div style=\"height: 200px\"></div>\
+            <div id=\"far\" style=\"width: 30px; height: 12px; background: #0000ff\"></div>\
+            <div id=\"hidden\" style=\"display: none\"></div>\
+        </body></html>",
+    );
+
+    let near = renderer.capture_element(&mut view, "#near").unwrap();
+    assert_eq!((near.width(), near.height()), (20, 10));
+    assert_eq!(&near.lock().unwrap()[..4], &[0, 255, 0, 255]);
+
+    // scrolled into view first
+    let far = renderer.capture_element(&mut view, "#far").unwrap();
+    assert_eq!((far.width(), far.height()), (30, 12));
+    assert_eq!(&far.lock().unwrap()[..4], &[255, 0, 0, 255]);
+    assert_eq!(view.scroll_position().unwrap(), (0.0, 0.0));
+
+    assert!(matches!(
+        renderer.capture_element(&mut view, "#missing"),
+        Err(ul::Error::ElementNotFound(_))
+    ));
+    assert!(matches!(
+        renderer.capture_element(&mut view, "#hidden"),
+        Err(ul::Error::ElementNotVisible(_))
+    ));
+}
+
+fn navigates_history(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        32,
+        "<html><head><title>First</title></head><body></body></html>",
+    );
+
+    assert_eq!(view.title().unwrap(), "First");
+    assert!(!view.can_go_back());
+    assert!(!view.can_go_forward());
+
+    view.evaluate_script("location.hash = 'second'").unwrap();
+    renderer
+        .wait_until(&mut view, &ul::WaitOptions::default(), |view| {
+            Ok(view.can_go_back())
+        })
+        .unwrap();
+    assert!(view.url().unwrap().ends_with("#second"));
+
+    view.go_back();
+    renderer
+        .wait_for_js(&mut view, "location.hash === ''", &ul::WaitOptions::default())
+        .unwrap();
+    assert!(view.can_go_forward());
+
+    view.go_to_history_offset(1);
+    renderer
+        .wait_for_js(&mut view, "location.hash === '#second'", &ul::WaitOptions::default())
+        .unwrap();
+    assert!(!view.can_go_forward());
+}
+
+fn calls_lifecycle_callbacks(renderer: &ul::Renderer) {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut view = renderer.create_view(64, 32, None);
+
+    let e = events.clone();
+    view.set_begin_loading_callback(move |_, event| {
+        if event.is_main_frame {
+            e.borrow_mut().push("begin_loading".to_owned());
+        }
+    });
+    let e = events.clone();
+    view.set_window_object_ready_callback(move |_, event| {
+        if event.is_main_frame {
+            e.borrow_mut().push("window_object_ready".to_owned());
+        }
+    });
+    let e = events.clone();
+    view.set_dom_ready_callback(move |_| e.borrow_mut().push("dom_ready".to_owned()));
+    let e = events.clone();
+    view.set_finish_loading_callback(move |_| e.borrow_mut().push("finish_loading".to_owned()));
+    let e = events.clone();
+    view.set_change_title_callback(move |_, title| e.borrow_mut().push(format!("title {}", title)));
+
+    view.load_html("<html><head><title>Loaded</title></head><body></body></html>")
+        .unwrap();
+    renderer
+        .update_until_loaded(&mut view, &ul::WaitOptions::default())
+        .unwrap();
+
+    let events = events.borrow();
+    let position = |name: &str| {
+        events
+            .iter()
+            .position(|event| event == name)
+            .unwrap_or_else(|| panic!("{} not called: {:?}", name, events))
+    };
+
+    assert!(position("begin_loading") < position("window_object_ready"));
+    assert!(position("window_object_ready") < position("dom_ready"));
+    assert!(position("dom_ready") < position("finish_loading"));
+    position("title Loaded");
+}
+
+fn reports_console_messages(renderer: &ul::Renderer) {
+    let messages = Rc::new(RefCell::new(Vec::new()));
+    let mut view = load(renderer, 64, 32, "<html><body></body></html>");
+
+    let m = messages.clone();
+    view.set_console_message_callback(move |_, message| m.borrow_mut().push(message));
+
+    view.evaluate_script("console.log('hello'); console.warn('careful')").unwrap();
+    renderer
+        .wait_until(&mut view, &ul::WaitOptions::default(), |_| {
+            Ok(messages.borrow().len() >= 2)
+        })
+        .unwrap();
+
+    let messages = messages.borrow();
+    assert_eq!(messages[0].source, ul::MessageSource::ConsoleAPI);
+    assert_eq!(messages[0].level, ul::MessageLevel::Log);
+    assert_eq!(messages[0].text, "hello");
+    assert_eq!(messages[1].level, ul::MessageLevel::Warning);
+    assert_eq!(messages[1].text, "careful");
+}
+
+fn waits_for_conditions(renderer: &ul::Renderer) {
+    let mut view = load(
+        renderer,
+        64,
+        32,
+        "<html><body><script>\
+            setTimeout(function() {\
+                document.body.appendChild(document.createElement('section'));\
+                window.ready = true;\
+            }, 50);\
+        </script></body></html>",
+    );
+
+    let options = ul::WaitOptions::default();
+    renderer.wait_for_selector(&mut view, "section", &options).unwrap();
+    renderer.wait_for_js(&mut view, "window.ready", &options).unwrap();
+    renderer.wait_for_network_idle(&mut view, &options).unwrap();
+
+    let options = ul::WaitOptions {
+        timeout: Duration::from_millis(100),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    assert!(matches!(
+        renderer.wait_for_selector(&mut view, "#missing", &options),
+        Err(ul::Error::Timeout(_))
+    ));
+    // exceptions count as falsy rather than failing the wait
+    assert!(matches!(
+        renderer.wait_for_js(&mut view, "window.neverSet.ready", &options),
+        Err(ul::Error::Timeout(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}